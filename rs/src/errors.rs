@@ -7,7 +7,7 @@ use ansi_term::Style;
 use backtrace::Backtrace;
 use std::process::exit;
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct ZyxtError {
     pub position: Vec<(Position, String)>,
     pub code: &'static str,
@@ -34,6 +34,18 @@ impl ZyxtError {
         }
     }
 
+    /// Maximum call depth exceeded
+    pub fn error_0_2(max_call_depth: usize) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "0.2",
+            message: format!(
+                "Maximum call depth of {} exceeded (Change it with `--max-call-depth`)",
+                max_call_depth
+            ),
+        }
+    }
+
     /* 1. File and I/O errors */
     /// File does not exist
     pub fn error_1_0(filename: String) -> Self {
@@ -233,11 +245,11 @@ impl ZyxtError {
         }
     }
     /// unexpected ident (parameters with class keyword)
-    pub fn error_2_1_17() -> Self {
+    pub fn error_2_1_17(kwd: &str) -> Self {
         ZyxtError {
             position: vec![],
             code: "2.1.17",
-            message: "Parameters found after `class` keyword".to_string(),
+            message: format!("Parameters found after `{}` keyword", kwd),
         }
     }
     /// unexpected ident (parameters with class keyword)
@@ -248,6 +260,89 @@ impl ZyxtError {
             message: format!("Block expected after `{:?}`", kwd),
         }
     }
+    /// unexpected ident (`do` block without `while`)
+    pub fn error_2_1_19() -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.1.19",
+            message: "`while` expected after `do` block".to_string(),
+        }
+    }
+    /// unexpected ident (`for` header without 3 parts)
+    pub fn error_2_1_20() -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.1.20",
+            message: "`for` header must be `{init; condition; step}`".to_string(),
+        }
+    }
+    /// unexpected ident (`match` without any `of`)
+    pub fn error_2_1_21() -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.1.21",
+            message: "`of` expected after `match`".to_string(),
+        }
+    }
+    /// unexpected ident (`of` found without `match` keyword)
+    pub fn error_2_1_22() -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.1.22",
+            message: "Stray `of` without starting `match`".to_string(),
+        }
+    }
+    /// unexpected ident (dict entry without a `:`)
+    pub fn error_2_1_23(entry: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.1.23",
+            message: format!("Dict entry must be `key: value` (Got `{}`)", entry),
+        }
+    }
+    /// unexpected ident (positional argument after keyword argument)
+    pub fn error_2_1_24(arg: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.1.24",
+            message: format!(
+                "Positional argument `{}` cannot come after keyword arguments",
+                arg
+            ),
+        }
+    }
+    /// unexpected ident (order of an infix procedure is not a number, optionally followed by `left` or `right`)
+    pub fn error_2_1_25(order: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.1.25",
+            message: format!(
+                "Infix order must be a number from 0 to 255, optionally followed by `left` or `right` (Got `{}`)",
+                order
+            ),
+        }
+    }
+    /// unexpected ident (infix procedure is not declared with a name)
+    pub fn error_2_1_26() -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.1.26",
+            message:
+                "Infix procedures must be declared with a name, eg `f := infix proc|a, b| {...}`"
+                    .to_string(),
+        }
+    }
+    /// unexpected ident (infix procedure takes fewer than 2 arguments)
+    pub fn error_2_1_27(args: usize) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.1.27",
+            message: format!(
+                "Infix procedures must take at least 2 arguments (Got {})",
+                args
+            ),
+        }
+    }
 
     /// expected pattern, got something else
     pub fn error_2_2(ele: Element) -> Self {
@@ -267,6 +362,100 @@ impl ZyxtError {
         }
     }
 
+    /// `break` / `continue` outside of a loop
+    pub fn error_2_4(kwd: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.4",
+            message: format!("`{}` outside of loop", kwd),
+        }
+    }
+
+    /// too many arguments
+    pub fn error_2_5_0(expected: usize, got: usize) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.5.0",
+            message: format!(
+                "Too many arguments; expected at most {} (Got {})",
+                expected, got
+            ),
+        }
+    }
+    /// unexpected keyword argument
+    pub fn error_2_5_1(arg: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.5.1",
+            message: format!("Unexpected keyword argument `{}`", arg),
+        }
+    }
+    /// argument given twice
+    pub fn error_2_5_2(arg: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.5.2",
+            message: format!("Argument `{}` given more than once", arg),
+        }
+    }
+    /// instance attribute declared in a struct
+    pub fn error_2_6_0(attr: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.6.0",
+            message: format!(
+                "Struct declares instance attribute `{}`; declare it as a field instead",
+                attr
+            ),
+        }
+    }
+    /// `_init` declared in a struct
+    pub fn error_2_6_1() -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.6.1",
+            message: "Struct declares `_init`; structs are constructed from their fields"
+                .to_string(),
+        }
+    }
+    /// `..` inheritance outside of a class
+    pub fn error_2_6_2() -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.6.2",
+            message: "Inheritance outside of a class, struct or mixin".to_string(),
+        }
+    }
+    /// enum item that is not a variant
+    pub fn error_2_6_3() -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.6.3",
+            message: "Enum variants are declared as `A`, `A := value` or `A := struct |...|`"
+                .to_string(),
+        }
+    }
+    /// enum variant declared twice
+    pub fn error_2_6_4(variant: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.6.4",
+            message: format!("Variant `{}` declared more than once", variant),
+        }
+    }
+
+    /// Something other than a variable referenced
+    pub fn error_2_7(ele: Element) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "2.7",
+            message: format!(
+                "Only variables can be referenced, got `{}`",
+                ele.get_raw().trim()
+            ),
+        }
+    }
+
     /* 3. Variable & attribute errors */
     /// Variable not defined
     pub fn error_3_0(varname: String) -> Self {
@@ -303,6 +492,108 @@ impl ZyxtError {
             ),
         }
     }
+    /// Enum has no variant
+    pub fn error_3_1_2(enum_: Type, variant: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "3.1.2",
+            message: format!("Enum `{}` has no variant `{}`", enum_, variant),
+        }
+    }
+
+    /// Undefined label
+    pub fn error_3_2(label: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "3.2",
+            message: format!("Undefined label `'{}`", label),
+        }
+    }
+
+    /// Captured variable reassigned inside a function
+    pub fn error_3_3(varname: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "3.3",
+            message: format!(
+                "Variable `{}` is captured by a function and cannot be changed inside it",
+                varname
+            ),
+        }
+    }
+
+    /// Constant changed or redeclared
+    pub fn error_3_4(varname: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "3.4",
+            message: format!("`{}` is constant and cannot be changed", varname),
+        }
+    }
+
+    /// Private attribute accessed outside its class
+    pub fn error_3_5_0(class: String, attribute: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "3.5.0",
+            message: format!(
+                "Attribute `{}` of `{}` is private and can only be accessed inside `{}`",
+                attribute, class, class
+            ),
+        }
+    }
+    /// Protected attribute accessed outside its class and its subclasses
+    pub fn error_3_5_1(class: String, attribute: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "3.5.1",
+            message: format!(
+                "Attribute `{}` of `{}` is protected and can only be accessed inside `{}` and classes inheriting from it",
+                attribute, class, class
+            ),
+        }
+    }
+    /// More than one of `pub`, `priv` and `prot` given to a declaration
+    pub fn error_3_5_2(flag1: String, flag2: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "3.5.2",
+            message: format!(
+                "Flags `{}` and `{}` cannot be used together, as a variable has only one visibility",
+                flag1, flag2
+            ),
+        }
+    }
+    /// `self` assigned to in a method
+    pub fn error_3_6() -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "3.6",
+            message: "`self` cannot be assigned to, only its attributes can be changed".to_string(),
+        }
+    }
+    /// Variable that a reference or pointer refers to redeclared or deleted
+    pub fn error_3_7(varname: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "3.7",
+            message: format!(
+                "Variable `{}` is referred to by a reference or pointer, and cannot be redeclared or deleted",
+                varname
+            ),
+        }
+    }
+    /// Procedure called before a procedure it uses is declared
+    pub fn error_3_8(proc: String, forward_ref: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "3.8",
+            message: format!(
+                "`{}` is called before `{}`, which it uses, is declared",
+                proc, forward_ref
+            ),
+        }
+    }
 
     /* 4. Type errors */
     /// Binary operator not implemented for type
@@ -311,7 +602,7 @@ impl ZyxtError {
             position: vec![],
             code: "4.0.0",
             message: format!(
-                "Operator {} not implemented for types `{}`, `{}`",
+                "Operator `{}` not implemented for types `{}`, `{}`",
                 operator, type1, type2
             ),
         }
@@ -321,7 +612,10 @@ impl ZyxtError {
         ZyxtError {
             position: vec![],
             code: "4.0.1",
-            message: format!("Operator {} not implemented for type `{}`", operator, type_),
+            message: format!(
+                "Operator `{}` not implemented for type `{}`",
+                operator, type_
+            ),
         }
     }
 
@@ -331,7 +625,7 @@ impl ZyxtError {
             position: vec![],
             code: "4.1.0",
             message: format!(
-                "Operator {} unsuccessful on `{}` (type `{}`), `{}` (type `{}`)",
+                "Operator `{}` unsuccessful on `{}` (type `{}`), `{}` (type `{}`)",
                 operator,
                 value1,
                 value1.get_type_obj(),
@@ -346,13 +640,29 @@ impl ZyxtError {
             position: vec![],
             code: "4.1.1",
             message: format!(
-                "Operator {} unsuccessful on `{}` (type `{}`)",
+                "Operator `{}` unsuccessful on `{}` (type `{}`)",
                 operator,
                 value,
                 value.get_type_obj()
             ),
         }
     }
+    /// Binary operation undefined for the values of the operands
+    pub fn error_4_1_2(operator: String, value1: Value, value2: Value, reason: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.1.2",
+            message: format!(
+                "Operator `{}` unsuccessful on `{}` (type `{}`), `{}` (type `{}`): {}",
+                operator,
+                value1,
+                value1.get_type_obj(),
+                value2,
+                value2.get_type_obj(),
+                reason
+            ),
+        }
+    }
 
     /// Non-i32 script return value
     pub fn error_4_2(value: Value) -> Self {
@@ -383,6 +693,252 @@ impl ZyxtError {
             message: format!("Block returns variable of type `{}` earlier on, but also returns variable of type `{}`", block_type, return_type)
         }
     }
+    /// Type is not iterable (typechecker)
+    pub fn error_4_5_0(type_: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.5.0",
+            message: format!("Type `{}` is not iterable", type_),
+        }
+    }
+    /// Value is not iterable (interpreter)
+    pub fn error_4_5_1(value: Value) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.5.1",
+            message: format!(
+                "`{}` (type `{}`) is not iterable",
+                value,
+                value.get_type_obj()
+            ),
+        }
+    }
+    /// Match arms evaluate to different types
+    pub fn error_4_6(type1: Type, type2: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.6",
+            message: format!(
+                "Match arms evaluate to different types `{}` and `{}`",
+                type1, type2
+            ),
+        }
+    }
+    /// Collection items are of different types
+    pub fn error_4_7(type1: Type, type2: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.7",
+            message: format!(
+                "Collection has items of different types `{}` and `{}`",
+                type1, type2
+            ),
+        }
+    }
+    /// Type is not a sequence
+    pub fn error_4_8_0(type_: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.8.0",
+            message: format!("Type `{}` is not a sequence", type_),
+        }
+    }
+    /// Sequence indexed with a non-integer
+    pub fn error_4_8_1(type_: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.8.1",
+            message: format!("Sequences must be indexed with integers (Got `{}`)", type_),
+        }
+    }
+    /// Index out of range
+    pub fn error_4_8_2(index: Value, len: usize) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.8.2",
+            message: format!("Index `{}` out of range for length {}", index, len),
+        }
+    }
+    /// Item of an immutable sequence assigned to
+    pub fn error_4_8_3(type_: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.8.3",
+            message: format!("Items of `{}` cannot be reassigned", type_),
+        }
+    }
+    /// Dict indexed with a key of the wrong type
+    pub fn error_4_8_4(key_type: Type, type_: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.8.4",
+            message: format!("Dict keys are of type `{}` (Got `{}`)", key_type, type_),
+        }
+    }
+    /// Key not in dict
+    pub fn error_4_8_5(key: Value) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.8.5",
+            message: format!("Key `{:?}` not found", key),
+        }
+    }
+
+    /// Argument of wrong type
+    pub fn error_4_9(arg: String, arg_type: Type, value_type: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.9",
+            message: format!(
+                "Value of type `{}` passed to argument `{}` of type `{}`",
+                value_type, arg, arg_type
+            ),
+        }
+    }
+    /// Mixin instantiated
+    pub fn error_4_10(mixin: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.10",
+            message: format!("Mixin `{}` cannot be instantiated", mixin),
+        }
+    }
+    /// Type cannot be inherited from
+    pub fn error_4_11_0(parent: Element, parent_type: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.11.0",
+            message: format!(
+                "Cannot inherit from `{}` (type `{}`); classes inherit from classes and mixins, and structs and mixins from mixins",
+                parent.get_raw().trim(),
+                parent_type
+            ),
+        }
+    }
+    /// No consistent method resolution order
+    pub fn error_4_11_1() -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.11.1",
+            message: "Parents are inherited in an order that conflicts with the order of their own parents".to_string(),
+        }
+    }
+    /// Enum variant is valued by a type other than a number, str or bool
+    pub fn error_4_12(variant: String, value_type: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.12",
+            message: format!(
+                "Variant `{}` has a value of type `{}`; enum variants are valued by numbers, strs and bools, or are structs",
+                variant, value_type
+            ),
+        }
+    }
+
+    /// No overload of a procedure accepts the arguments given
+    pub fn error_4_13_0(arg_types: Vec<Type>) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.13.0",
+            message: format!(
+                "No overload accepts arguments of types `{}`",
+                arg_types
+                    .iter()
+                    .map(|arg_type| arg_type.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+    /// More than one overload of a procedure accepts the arguments given
+    pub fn error_4_13_1(arg_types: Vec<Type>) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.13.1",
+            message: format!(
+                "More than one overload accepts arguments of types `{}`",
+                arg_types
+                    .iter()
+                    .map(|arg_type| arg_type.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    /// A value that may be null is used without checking that it is not
+    pub fn error_4_14_0(type_: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.14.0",
+            message: format!(
+                "Value of type `{}` may be null; check that it is not null, or use `?.`, `?:` or `!!`",
+                type_
+            ),
+        }
+    }
+    /// `!!` used on a value that is null
+    pub fn error_4_14_1(value: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.14.1",
+            message: format!("`{}` is null", value),
+        }
+    }
+    /// A value that may be an error is used without handling the error
+    pub fn error_4_15_0(type_: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.15.0",
+            message: format!(
+                "Value of type `{}` may be an error; handle it with `!?:`, or assert that it is not with `!!`",
+                type_
+            ),
+        }
+    }
+    /// The handler given to `!?:` is not a procedure that takes the error
+    pub fn error_4_15_1(handler_type: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.15.1",
+            message: format!(
+                "Error handler of type `{}` is not a procedure that takes an `err`",
+                handler_type
+            ),
+        }
+    }
+
+    /// Variable changed through a reference instead of a pointer
+    pub fn error_4_16(reference: String, ref_type: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.16",
+            message: format!(
+                "`{}` (type `{}`) is a reference, and cannot change the variable it refers to. Use a pointer (`&>`) instead",
+                reference, ref_type
+            ),
+        }
+    }
+
+    /// Condition of an `if`, a loop or a `for` header that is not a bool
+    pub fn error_4_17(condition_type: Type) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "4.17",
+            message: format!("Condition is of type `{}`, not `bool`", condition_type),
+        }
+    }
+
+    /* 5. Errors raised by the program */
+    /// Error raised with `raise`
+    pub fn error_5_0(message: String) -> Self {
+        ZyxtError {
+            position: vec![],
+            code: "5.0",
+            message,
+        }
+    }
+
     pub fn print_exit(self, out: &mut impl Print) -> ! {
         self.print(out);
         exit(1)
@@ -414,6 +970,16 @@ impl ZyxtError {
         )];
         self
     }
+    /// Adds the positions of elements after the position of the error, eg the definitions involved
+    pub fn with_more_elements(mut self, elements: &[Element]) -> Self {
+        self.position.extend(elements.iter().map(|element| {
+            (
+                element.get_pos().to_owned(),
+                element.get_raw().trim().to_string(),
+            )
+        }));
+        self
+    }
     pub fn with_token(mut self, token: &Token) -> Self {
         self.position = vec![(
             token.position.to_owned(),
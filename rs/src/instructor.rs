@@ -1,14 +1,18 @@
 use crate::objects::element::Element;
 use crate::objects::interpreter_data::{InterpreterData, Print};
+use crate::objects::logger::Logger;
 use crate::objects::typeobj::Type;
 use crate::ZyxtError;
 
-pub fn gen_instructions<O: Print>(
+pub fn gen_instructions<O: Print, L: Print>(
     mut input: Vec<Element>,
     typelist: &mut InterpreterData<Type, O>,
+    logger: &mut Logger<L>,
 ) -> Result<Vec<Element>, ZyxtError> {
+    Element::hoist(&mut input);
+    Element::declare_procs(&input, typelist);
     for ele in input.iter_mut() {
-        ele.eval_type(typelist)?;
+        ele.eval_type(typelist, logger)?;
     }
     Ok(input)
 }
@@ -1,14 +1,37 @@
-use crate::objects::element::{Argument, Element};
-use crate::objects::interpreter_data::{FrameData, InterpreterData, Print};
+use crate::objects::control_flow::ControlFlow;
+use crate::objects::element::{Argument, Element, Pattern};
+use crate::objects::interpreter_data::{Environment, FrameData, InterpreterData, Print, Slot};
+use crate::objects::position::Position;
 use crate::objects::token::OprType;
-use crate::objects::value::{logic, Value};
+use crate::objects::typeobj::Param;
+use crate::objects::value::iter::iter;
+use crate::objects::value::utils::OprError;
+use crate::objects::value::{logic, seq, Value};
 use crate::{Type, ZyxtError};
+use indexmap::{IndexMap, IndexSet};
 use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Stack space that must be left for an expression to be interpreted without growing the stack
+const STACK_RED_ZONE: usize = 1024 * 1024;
+/// Stack space added whenever the interpreter runs low on it
+const STACK_GROWTH: usize = 16 * 1024 * 1024;
 
 pub fn interpret_expr<O: Print>(
     input: &Element,
     i_data: &mut InterpreterData<Value, O>,
-) -> Result<Value, ZyxtError> {
+) -> Result<Value, ControlFlow> {
+    // the interpreter recurses on the Rust stack, which is grown as calls and expressions nest
+    // instead of being reserved for the max call depth beforehand
+    stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, || {
+        interpret_expr_unchecked(input, i_data)
+    })
+}
+
+fn interpret_expr_unchecked<O: Print>(
+    input: &Element,
+    i_data: &mut InterpreterData<Value, O>,
+) -> Result<Value, ControlFlow> {
     match input {
         Element::Token(..) | Element::Comment { .. } | Element::Preprocess { .. } => panic!(),
         Element::NullElement => Ok(Value::Null),
@@ -19,14 +42,56 @@ pub fn interpret_expr<O: Print>(
             raw,
             ..
         } => {
-            if let Ok(v) = interpret_expr(operand, i_data)?.un_opr(type_) {
-                Ok(v)
-            } else {
-                Err(
-                    ZyxtError::error_4_1_1(type_.to_string(), interpret_expr(operand, i_data)?)
-                        .with_pos_and_raw(position, raw),
-                )
+            if let OprType::Ref | OprType::Pointer = type_ {
+                let slot = i_data.get_slot(&operand.get_name(), position, raw)?;
+                return Ok(Value::Ref {
+                    is_pointer: type_ == &OprType::Pointer,
+                    type_: slot.get().unwrap_or(Value::Null).get_type_obj(),
+                    slot,
+                });
+            }
+            let target: &Element = operand;
+            let operand_raw = operand.get_raw();
+            let operand = interpret_expr(operand, i_data)?;
+            if type_ == &OprType::Deref {
+                return if let Value::Ref { slot, .. } = &operand {
+                    // the variable may have been deleted since it was referenced
+                    slot.get().ok_or_else(|| {
+                        ZyxtError::error_3_0(slot.name.to_owned())
+                            .with_pos_and_raw(position, raw)
+                            .into()
+                    })
+                } else {
+                    Err(ZyxtError::error_4_1_1(type_.to_string(), operand)
+                        .with_pos_and_raw(position, raw)
+                        .into())
+                };
+            }
+            if type_ == &OprType::Assert {
+                return if let Value::Error(e) = operand {
+                    Err(e.into())
+                } else if operand == Value::Null {
+                    Err(ZyxtError::error_4_14_1(operand_raw.trim().to_string())
+                        .with_pos_and_raw(position, raw)
+                        .into())
+                } else {
+                    Ok(operand)
+                };
+            }
+            let value =
+                if let Some(v) = overloaded_opr(type_, &operand, None, i_data, position, raw)? {
+                    v
+                } else if let Ok(v) = operand.un_opr(type_) {
+                    v
+                } else {
+                    return Err(ZyxtError::error_4_1_1(type_.to_string(), operand)
+                        .with_pos_and_raw(position, raw)
+                        .into());
+                };
+            if let OprType::Increment | OprType::Decrement = type_ {
+                assign(target, value.to_owned(), i_data, position, raw)?;
             }
+            Ok(value)
         }
         Element::BinaryOpr {
             type_,
@@ -36,28 +101,88 @@ pub fn interpret_expr<O: Print>(
             raw,
             ..
         } => {
-            if let Ok(v) = match type_ {
-                OprType::And => Ok(logic::and(operand1, operand2, i_data)?),
-                OprType::Or => Ok(logic::or(operand1, operand2, i_data)?),
-                _ => interpret_expr(operand1, i_data)?
-                    .bin_opr(type_, interpret_expr(operand2, i_data)?),
-            } {
+            match type_ {
+                OprType::Elvis => return logic::elvis(operand1, operand2, i_data),
+                // errors raised by the first operand are given to the handler
+                OprType::ErrorHandle => {
+                    return match interpret_expr(operand1, i_data) {
+                        Ok(Value::Error(e)) | Err(ControlFlow::Error(e)) => {
+                            let handler = interpret_expr(operand2, i_data)?;
+                            call(
+                                handler,
+                                vec![Value::Error(e)],
+                                HashMap::new(),
+                                i_data,
+                                position,
+                                raw,
+                            )
+                        }
+                        res => res,
+                    }
+                }
+                OprType::Swap => {
+                    let value1 = interpret_expr(operand1, i_data)?;
+                    let value2 = interpret_expr(operand2, i_data)?;
+                    assign(operand1, value2, i_data, position, raw)?;
+                    assign(operand2, value1, i_data, position, raw)?;
+                    return Ok(Value::Null);
+                }
+                OprType::Is | OprType::Isnt => {
+                    let slot1 = identity(operand1, i_data)?;
+                    let slot2 = identity(operand2, i_data)?;
+                    let same = slot1.is_some() && slot1 == slot2;
+                    return Ok(Value::Bool(same == (type_ == &OprType::Is)));
+                }
+                _ => (),
+            }
+            let operand1 = interpret_expr(operand1, i_data)?;
+            // the right of `&&` and `||` is not evaluated if the left decides the result
+            if [OprType::And, OprType::Or].contains(type_)
+                && operand1 == Value::Bool(type_ == &OprType::Or)
+            {
+                return Ok(operand1);
+            }
+            let operand2 = interpret_expr(operand2, i_data)?;
+            if let Some(v) =
+                overloaded_opr(type_, &operand1, Some(&operand2), i_data, position, raw)?
+            {
                 Ok(v)
             } else {
-                Err(ZyxtError::error_4_1_0(
-                    type_.to_string(),
-                    interpret_expr(operand1, i_data)?,
-                    interpret_expr(operand2, i_data)?,
-                )
-                .with_pos_and_raw(position, raw))
+                match operand1.bin_opr(type_, operand2.to_owned()) {
+                    Ok(v) => Ok(v),
+                    Err(OprError::ValueError(reason)) => {
+                        Err(
+                            ZyxtError::error_4_1_2(type_.to_string(), operand1, operand2, reason)
+                                .with_pos_and_raw(position, raw)
+                                .into(),
+                        )
+                    }
+                    Err(_) => Err(
+                        ZyxtError::error_4_1_0(type_.to_string(), operand1, operand2)
+                            .with_pos_and_raw(position, raw)
+                            .into(),
+                    ),
+                }
             }
         }
         Element::Variable {
             name,
             position,
             raw,
-            ..
-        } => i_data.get_val(name, position, raw),
+            parent,
+        } => {
+            if **parent == Element::NullElement {
+                Ok(i_data.get_val(name, position, raw)?)
+            } else {
+                let parent_value = interpret_expr(parent, i_data)?;
+                // attributes accessed with `?.` on null are null
+                if matches!(**parent, Element::NullSafe { .. }) && parent_value == Value::Null {
+                    return Ok(Value::Null);
+                }
+                get_attr(&parent_value, name, i_data, position, raw)
+            }
+        }
+        Element::NullSafe { operand, .. } => interpret_expr(operand, i_data),
         Element::Declare {
             variable, content, ..
         } => {
@@ -72,9 +197,84 @@ pub fn interpret_expr<O: Print>(
             raw,
             ..
         } => {
-            let var = interpret_expr(content, i_data);
-            i_data.set_val(&variable.get_name(), &var.to_owned()?, position, raw)?;
-            var
+            let var = interpret_expr(content, i_data)?;
+            assign(variable, var.to_owned(), i_data, position, raw)?;
+            Ok(var)
+        }
+        Element::Array {
+            is_vec, content, ..
+        } => {
+            let content = content
+                .iter()
+                .map(|item| interpret_expr(item, i_data))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::Array {
+                is_vec: *is_vec,
+                type_: content
+                    .first()
+                    .map(|item| item.get_type_obj())
+                    .unwrap_or_else(Type::any),
+                content,
+            })
+        }
+        Element::Tuple { content, .. } => Ok(Value::Tuple(
+            content
+                .iter()
+                .map(|item| interpret_expr(item, i_data))
+                .collect::<Result<Vec<_>, _>>()?,
+        )),
+        Element::SetLiteral {
+            is_frozen, content, ..
+        } => {
+            let content = content
+                .iter()
+                .map(|item| interpret_expr(item, i_data))
+                .collect::<Result<IndexSet<_>, _>>()?;
+            Ok(Value::Set {
+                is_frozen: *is_frozen,
+                type_: content
+                    .first()
+                    .map(|item| item.get_type_obj())
+                    .unwrap_or_else(Type::any),
+                content,
+            })
+        }
+        Element::Dict { content, .. } => {
+            let content = content
+                .iter()
+                .map(|(k, v)| Ok((interpret_expr(k, i_data)?, interpret_expr(v, i_data)?)))
+                .collect::<Result<IndexMap<_, _>, ControlFlow>>()?;
+            let (key_type, value_type) = content
+                .first()
+                .map(|(k, v)| (k.get_type_obj(), v.get_type_obj()))
+                .unwrap_or_else(|| (Type::any(), Type::any()));
+            Ok(Value::Dict {
+                key_type,
+                value_type,
+                content,
+            })
+        }
+        Element::Index {
+            parent,
+            index,
+            position,
+            raw,
+        } => {
+            let parent = interpret_expr(parent, i_data)?;
+            let index = interpret_expr(index, i_data)?;
+            Ok(seq::index(&parent, &index, position, raw)?)
+        }
+        Element::Slice {
+            parent,
+            start,
+            end,
+            position,
+            raw,
+        } => {
+            let parent = interpret_expr(parent, i_data)?;
+            let start = interpret_expr(start, i_data)?;
+            let end = interpret_expr(end, i_data)?;
+            Ok(seq::slice(&parent, &start, &end, position, raw)?)
         }
         Element::Literal { type_, content, .. } => Ok(Value::from_type_content(
             type_.to_owned(),
@@ -83,82 +283,192 @@ pub fn interpret_expr<O: Print>(
         Element::Call {
             called,
             args: input_args,
+            kwargs: input_kwargs,
             position,
             raw,
-            ..
         } => {
             if let Element::Variable { parent, name, .. } = called.as_ref() {
-                if *name == "out" && parent.get_name() == *"ter" {
+                if name == "out"
+                    && matches!(&**parent, Element::Variable { name, .. } if name == "ter")
+                {
                     let s = input_args
-                            .iter()
-                            .map(|arg| interpret_expr(arg, i_data))
-                            .collect::<Result<Vec<_>, _>>()?
-                            .into_iter()
-                            .map(|v| v.to_string())
-                            .collect::<Vec<String>>()
-                            .join(" ");
+                        .iter()
+                        .map(|arg| interpret_expr(arg, i_data))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into_iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<String>>()
+                        .join(" ");
                     i_data.out.println(s);
                     return Ok(Value::Null);
                 }
+                // builtins are only called if no variable of the same name is declared
+                if name == "len"
+                    && **parent == Element::NullElement
+                    && input_args.len() == 1
+                    && !i_data.is_declared(name)
+                {
+                    let seq = interpret_expr(&input_args[0], i_data)?;
+                    return Ok(Value::Usize(seq::len(&seq, position, raw)?));
+                }
+                if name == "raise"
+                    && **parent == Element::NullElement
+                    && input_args.len() == 1
+                    && !i_data.is_declared(name)
+                {
+                    return Err(match interpret_expr(&input_args[0], i_data)? {
+                        Value::Error(e) => e,
+                        message => ZyxtError::error_5_0(message.to_string())
+                            .with_pos_and_raw(position, raw),
+                    }
+                    .into());
+                }
             }
-            let to_call = interpret_expr(called, i_data)?;
-            if let Value::Proc {
-                is_fn,
-                args,
-                content,
-                ..
-            } = to_call
-            {
-                let mut processed_args = HashMap::new();
-                for (cursor, Argument { name, ref default, .. }) in args.into_iter().enumerate() {
-                    let input_arg = if input_args.len() > cursor {
-                        input_args.get(cursor).unwrap()
-                    } else {
-                        default.as_ref().unwrap()
+            let mut input_args = input_args.iter();
+            // the instance a method is called on, and where to keep the changes made to it
+            let (to_call, self_, self_element) = match called.as_ref() {
+                Element::Variable { parent, name, .. } if **parent != Element::NullElement => {
+                    let parent_value = interpret_expr(parent, i_data)?;
+                    // methods called with `?.` on null are not called, and neither are their arguments evaluated
+                    let parent = match &**parent {
+                        Element::NullSafe { operand, .. } if parent_value == Value::Null => {
+                            return Ok(Value::Null)
+                        }
+                        Element::NullSafe { operand, .. } => operand,
+                        parent => parent,
                     };
-                    processed_args.insert(name, interpret_expr(input_arg, i_data)?);
+                    let method = get_attr(&parent_value, name, i_data, position, raw)?;
+                    if is_method(&parent_value, name, &method) {
+                        (method, Some(parent_value), Some(parent))
+                    } else if matches!(parent_value, Value::Type(_)) && takes_self(&method) {
+                        // methods called through a class, like `Parent.f(self)`, are given the instance explicitly
+                        let self_element = input_args.next();
+                        let self_ = self_element
+                            .map(|arg| interpret_expr(arg, i_data))
+                            .transpose()?;
+                        (method, self_, self_element)
+                    } else {
+                        (method, None, None)
+                    }
                 }
-
-                if is_fn {
-                    let mut fn_i_data = InterpreterData::default_variable(i_data.out);
-                    fn_i_data.heap.last_mut().unwrap().extend(processed_args);
-                    let res = interpret_block(&content, &mut fn_i_data, true, false);
-                    fn_i_data.pop_frame()?;
-                    res
-                } else {
-                    i_data.add_frame(Some(FrameData {
-                        position: position.to_owned(),
-                        raw_call: raw.to_owned(),
-                        args: processed_args.to_owned(),
-                    }));
-                    i_data.heap.last_mut().unwrap().extend(processed_args);
-                    let res = interpret_block(&content, i_data, true, false);
-                    i_data.pop_frame()?;
-                    res
+                _ => (interpret_expr(called, i_data)?, None, None),
+            };
+            let input_args = input_args
+                .map(|a| interpret_expr(a, i_data))
+                .collect::<Result<Vec<_>, _>>()?;
+            let input_kwargs = input_kwargs
+                .iter()
+                .map(|(k, a)| Ok((k.to_owned(), interpret_expr(a, i_data)?)))
+                .collect::<Result<HashMap<_, _>, ControlFlow>>()?;
+            let (res, new_self) = call_with_self(
+                to_call,
+                self_.to_owned(),
+                input_args,
+                input_kwargs,
+                i_data,
+                position,
+                raw,
+            )?;
+            // keep the changes the method made to the instance it was called on
+            if let (Some(self_element), Some(new_self)) = (self_element, new_self) {
+                if self_element.root_variable().is_pattern() && Some(&new_self) != self_.as_ref() {
+                    assign(self_element, new_self, i_data, position, raw)?;
                 }
-            } else if let Ok(v) = to_call.call(
-                input_args
-                    .iter()
-                    .map(|a| interpret_expr(a, i_data))
-                    .collect::<Result<Vec<_>, _>>()?,
-            ) {
-                Ok(v)
-            } else {
-                Err(ZyxtError::error_3_1_1(to_call, "_call".to_string())
-                    .with_pos_and_raw(position, raw))
             }
+            Ok(res)
         }
         Element::If { conditions, .. } => {
             for cond in conditions {
                 if cond.condition == Element::NullElement {
-                    return interpret_block(&cond.if_true, i_data, false, true);
+                    return interpret_block(&cond.if_true, i_data, true);
                 } else if let Value::Bool(true) = interpret_expr(&cond.condition, i_data)? {
-                    return interpret_block(&cond.if_true, i_data, false, true);
+                    return interpret_block(&cond.if_true, i_data, true);
+                }
+            }
+            Ok(Value::Null)
+        }
+        Element::Match { subject, arms, .. } => {
+            let subject = interpret_expr(subject, i_data)?;
+            for arm in arms {
+                let mut bindings = HashMap::new();
+                if match_pattern(&arm.pattern, &subject, i_data, &mut bindings)? {
+                    i_data.add_frame(None);
+                    for (name, value) in bindings {
+                        i_data.declare_val(&name, &value);
+                    }
+                    let res = interpret_block(&arm.content, i_data, false);
+                    return i_data.pop_frame().and(res);
+                }
+            }
+            Ok(Value::Null)
+        }
+        Element::While {
+            condition,
+            content,
+            do_first,
+            label,
+            ..
+        } => {
+            let mut skip_check = *do_first;
+            loop {
+                if !skip_check
+                    && **condition != Element::NullElement
+                    && interpret_expr(condition, i_data)? != Value::Bool(true)
+                {
+                    break;
+                }
+                skip_check = false;
+                if !continues_loop(interpret_block(content, i_data, true), label)? {
+                    break;
+                }
+            }
+            Ok(Value::Null)
+        }
+        Element::For {
+            variable,
+            type_,
+            iterable,
+            content,
+            label,
+            position,
+            raw,
+        } => {
+            let iterable = interpret_expr(iterable, i_data)?;
+            for mut item in iter(&iterable, i_data, position, raw)? {
+                if *type_ != Type::any() && item.get_type_obj() != *type_ {
+                    let target = Value::Type(type_.to_owned());
+                    item = item
+                        .bin_opr(&OprType::TypeCast, target.to_owned())
+                        .map_err(|_| {
+                            ZyxtError::error_4_1_0(OprType::TypeCast.to_string(), item, target)
+                                .with_pos_and_raw(position, raw)
+                        })?;
+                }
+                i_data.add_frame(None);
+                i_data.declare_val(&variable.get_name(), &item);
+                let res = interpret_block(content, i_data, false);
+                if !continues_loop(i_data.pop_frame().and(res), label)? {
+                    break;
                 }
             }
             Ok(Value::Null)
         }
-        Element::Block { content, .. } => interpret_block(content, i_data, true, true),
+        Element::CStyleFor {
+            init,
+            condition,
+            step,
+            content,
+            label,
+            ..
+        } => {
+            i_data.add_frame(None);
+            let res = interpret_c_style_for(init, condition, step, content, label, i_data);
+            i_data.pop_frame().and(res)
+        }
+        Element::Block { content, .. } => match interpret_block(content, i_data, true) {
+            Err(ControlFlow::Return { label: None, value }) => Ok(*value),
+            res => res,
+        },
         Element::Delete {
             names,
             position,
@@ -170,135 +480,918 @@ pub fn interpret_expr<O: Print>(
             }
             Ok(Value::Null)
         }
-        Element::Return { value, .. } => {
-            Ok(Value::Return(Box::new(interpret_expr(value, i_data)?)))
-        }
+        Element::Return { value, label, .. } => Err(ControlFlow::Return {
+            label: label.to_owned(),
+            value: Box::new(interpret_expr(value, i_data)?),
+        }),
+        Element::Break { label, .. } => Err(ControlFlow::Break {
+            label: label.to_owned(),
+        }),
+        Element::Continue { label, .. } => Err(ControlFlow::Continue {
+            label: label.to_owned(),
+        }),
         Element::Procedure {
             is_fn,
             args,
             return_type,
             content,
+            label,
             ..
         } => Ok(Value::Proc {
             is_fn: *is_fn,
             args: args.to_owned(),
             return_type: return_type.to_owned(),
             content: content.to_owned(),
+            label: label.to_owned(),
+            environment: Environment(i_data.heap.to_owned()),
         }),
+        Element::Overload { procs, .. } => Ok(Value::Overload(
+            procs
+                .iter()
+                .map(|proc| interpret_expr(proc, i_data))
+                .collect::<Result<Vec<_>, _>>()?,
+        )),
         Element::Defer { content, .. } => {
             i_data.add_defer(content.to_owned());
             Ok(Value::Null)
         }
         Element::Class {
+            name,
             class_attrs,
             inst_attrs,
             is_struct,
+            is_mixin,
+            generics,
+            args,
+            content,
             ..
-        } => Ok(Value::Type(Type::Definition {
-            name: if *is_struct { "struct" } else { "class" }.to_string(),
-            generics: vec![],
-            class_attrs: class_attrs.to_owned(),
-            inst_attrs: inst_attrs.to_owned(),
-        })),
+        } => {
+            let mut parents = vec![];
+            for expr in content {
+                if let Element::Inherit { parent, .. } = expr {
+                    if let Value::Type(parent) = interpret_expr(parent, i_data)? {
+                        parents.push(parent);
+                    }
+                }
+            }
+            Ok(Value::Type(Type::Definition {
+                name: name.to_owned().unwrap_or_default(),
+                is_struct: *is_struct,
+                is_mixin: *is_mixin,
+                generics: generics.to_owned(),
+                fields: args.to_owned().unwrap_or_default(),
+                parents,
+                class_attrs: class_attrs.to_owned(),
+                inst_attrs: inst_attrs.to_owned(),
+                init_attrs: Default::default(),
+                attr_flags: Box::new(Element::attr_flags(content)),
+            }))
+        }
+        Element::Inherit { .. } => Ok(Value::Null),
+        Element::Enum { name, variants, .. } => {
+            // the values of variants are worked out once, when the enum is made
+            let mut evaluated = IndexMap::new();
+            for (variant, value) in variants {
+                evaluated.insert(
+                    variant.to_owned(),
+                    match value {
+                        Element::Class { .. } | Element::NullElement => value.to_owned(),
+                        _ => interpret_expr(value, i_data)?.as_element(),
+                    },
+                );
+            }
+            Ok(Value::Type(Type::Enum {
+                name: name.to_owned().unwrap_or_default(),
+                variants: evaluated,
+            }))
+        }
     }
 }
 
-pub fn interpret_block<O: Print>(
-    input: &Vec<Element>,
+/// Gets an attribute of an instance, or of the class it or a class value belongs to
+pub fn get_attr<O: Print>(
+    parent: &Value,
+    name: &String,
     i_data: &mut InterpreterData<Value, O>,
-    returnable: bool,
-    add_frame: bool,
-) -> Result<Value, ZyxtError> {
-    let mut last = Value::Null;
+    position: &Position,
+    raw: &String,
+) -> Result<Value, ControlFlow> {
+    let attr = match parent {
+        Value::ClassInstance { attrs, .. } if attrs.contains_key(name) => {
+            return Ok(attrs[name].to_owned())
+        }
+        Value::Type(type_ @ Type::Enum { variants, .. }) => {
+            return if let Some(value) = variants.get(name) {
+                Ok(Value::EnumVariant {
+                    type_: type_.instance_type(),
+                    variant: name.to_owned(),
+                    value: Box::new(interpret_expr(value, i_data)?),
+                })
+            } else {
+                Err(ZyxtError::error_3_1_2(type_.to_owned(), name.to_owned())
+                    .with_pos_and_raw(position, raw)
+                    .into())
+            };
+        }
+        // the fields of struct variants are those of their struct
+        Value::EnumVariant { value, .. } if matches!(**value, Value::ClassInstance { .. }) => {
+            return get_attr(value, name, i_data, position, raw);
+        }
+        Value::ClassInstance { type_, .. } | Value::Type(type_) => {
+            type_.get_attrs().get(name).cloned()
+        }
+        Value::Error(e) => {
+            return match &**name {
+                "code" => Ok(Value::Str(e.code.to_string())),
+                "message" => Ok(Value::Str(e.message.to_owned())),
+                "position" => Ok(Value::Str(
+                    e.position
+                        .first()
+                        .map(|(position, _)| position.to_string())
+                        .unwrap_or_default(),
+                )),
+                _ => Err(ZyxtError::error_3_1_1(parent.to_owned(), name.to_owned())
+                    .with_pos_and_raw(position, raw)
+                    .into()),
+            }
+        }
+        _ => None,
+    };
+    if let Some(attr) = attr {
+        interpret_expr(&attr, i_data)
+    } else {
+        Err(ZyxtError::error_3_1_1(parent.to_owned(), name.to_owned())
+            .with_pos_and_raw(position, raw)
+            .into())
+    }
+}
 
-    macro_rules! pop {
-        () => {
-            if add_frame {
-                let res = i_data.pop_frame()?;
-                if let Some(res) = res {
-                    return Ok(res);
+/// Calls the magic method overloading an operator on the first operand,
+/// or the reflected one on the second operand of a binary operator.
+/// None if neither operand overloads the operator
+fn overloaded_opr<O: Print>(
+    type_: &OprType,
+    operand1: &Value,
+    operand2: Option<&Value>,
+    i_data: &mut InterpreterData<Value, O>,
+    position: &Position,
+    raw: &String,
+) -> Result<Option<Value>, ControlFlow> {
+    let magic_method =
+        |operand: &Value, other: Option<&Value>, name: Option<String>| match (operand, name) {
+            (Value::ClassInstance { type_, .. }, Some(name)) => {
+                match type_.get_attrs().get(&name) {
+                    Some(Element::Procedure { args, .. })
+                        if !Element::overload_accepts(
+                            args,
+                            type_,
+                            other.map(|other| other.get_type_obj()).as_ref(),
+                        ) =>
+                    {
+                        None
+                    }
+                    method => method.cloned(),
                 }
             }
+            _ => None,
         };
+    let (method, self_, other) = if let Some(method) =
+        magic_method(operand1, operand2, type_.magic_method().map(String::from))
+    {
+        (method, operand1, operand2)
+    } else if let Some(operand2) = operand2 {
+        if let Some(method) = magic_method(operand2, Some(operand1), type_.reflected_magic_method())
+        {
+            (method, operand2, Some(operand1))
+        } else {
+            return Ok(None);
+        }
+    } else {
+        return Ok(None);
+    };
+    let method = interpret_expr(&method, i_data)?;
+    Ok(Some(
+        call_with_self(
+            method,
+            Some(self_.to_owned()),
+            other.into_iter().cloned().collect(),
+            HashMap::new(),
+            i_data,
+            position,
+            raw,
+        )?
+        .0,
+    ))
+}
+
+/// Whether a value is a procedure taking `self` first
+fn takes_self(attr: &Value) -> bool {
+    match attr {
+        Value::Proc { args, .. } => args.first().is_some_and(|arg| arg.name == "self"),
+        Value::Overload(procs) => procs.iter().all(takes_self),
+        _ => false,
     }
+}
 
-    if add_frame {
-        i_data.add_frame(None);
+/// Converts the numbers passed to an overload to the number types of its parameters,
+/// the same way the checker converts them when it chooses the overload
+fn coerce_num_args(
+    params: &[Param],
+    args: &[Value],
+    kwargs: &HashMap<String, Value>,
+) -> (Vec<Value>, HashMap<String, Value>) {
+    let coerce = |value: &Value, type_: &Type| {
+        if value.is_num() && type_.non_null().is_num() && !type_.accepts(&value.get_type_obj()) {
+            value
+                .bin_opr(&OprType::TypeCast, Value::Type(type_.non_null()))
+                .unwrap_or_else(|_| value.to_owned())
+        } else {
+            value.to_owned()
+        }
+    };
+    let mut args = args.iter();
+    let mut coerced_args = vec![];
+    let mut coerced_kwargs = HashMap::new();
+    let mut vkwarg = None;
+    for param in params {
+        if param.type_.is_varg() {
+            let item_type = param.type_.variadic_item_type();
+            coerced_args.extend(args.by_ref().map(|arg| coerce(arg, &item_type)));
+        } else if param.type_.is_vkwarg() {
+            vkwarg = Some(param.type_.variadic_item_type());
+        } else if let Some(arg) = args.next() {
+            coerced_args.push(coerce(arg, &param.type_));
+        } else if let Some(kwarg) = kwargs.get(&param.name) {
+            coerced_kwargs.insert(param.name.to_owned(), coerce(kwarg, &param.type_));
+        }
     }
-    for ele in input {
-        if let Element::Return { value, .. } = &ele {
-            if returnable {
-                last = interpret_expr(value, i_data)?
+    coerced_args.extend(args.cloned());
+    for (name, kwarg) in kwargs {
+        if !coerced_kwargs.contains_key(name) {
+            let kwarg = match &vkwarg {
+                Some(item_type) => coerce(kwarg, item_type),
+                None => kwarg.to_owned(),
+            };
+            coerced_kwargs.insert(name.to_owned(), kwarg);
+        }
+    }
+    (coerced_args, coerced_kwargs)
+}
+
+/// Whether an attribute of a value is a method of its class
+fn is_method(parent: &Value, name: &String, attr: &Value) -> bool {
+    matches!(parent, Value::ClassInstance { attrs, .. } if !attrs.contains_key(name))
+        && takes_self(attr)
+}
+
+pub fn call<O: Print>(
+    to_call: Value,
+    input_args: Vec<Value>,
+    input_kwargs: HashMap<String, Value>,
+    i_data: &mut InterpreterData<Value, O>,
+    position: &Position,
+    raw: &String,
+) -> Result<Value, ControlFlow> {
+    Ok(call_with_self(
+        to_call,
+        None,
+        input_args,
+        input_kwargs,
+        i_data,
+        position,
+        raw,
+    )?
+    .0)
+}
+
+/// Binds the arguments of a call to the parameters of a procedure or the fields of a struct.
+/// The first `skipped` arguments were passed by the interpreter and are not counted in errors
+fn bind_args<O: Print>(
+    args: Vec<Argument>,
+    input_args: Vec<Value>,
+    mut input_kwargs: HashMap<String, Value>,
+    skipped: usize,
+    i_data: &mut InterpreterData<Value, O>,
+    position: &Position,
+    raw: &String,
+) -> Result<HashMap<String, Value>, ControlFlow> {
+    // variable keyword arguments only take keyword arguments
+    let max_args = args
+        .iter()
+        .filter(|arg| !arg.is_vkwarg())
+        .count()
+        .saturating_sub(skipped);
+    let got_args = input_args.len().saturating_sub(skipped);
+    let mut input_args = input_args.into_iter();
+    let mut processed_args = HashMap::new();
+    let mut vkwarg = None;
+    for arg in args {
+        let input_arg = if arg.is_varg() {
+            Value::Array {
+                is_vec: false,
+                type_: arg.item_type(),
+                content: input_args.by_ref().collect(),
+            }
+        } else if arg.is_vkwarg() {
+            // filled in once all the other arguments have taken their keyword arguments
+            vkwarg = Some(arg);
+            continue;
+        } else if let Some(input_arg) = input_args.next() {
+            if input_kwargs.contains_key(&arg.name) {
+                return Err(ZyxtError::error_2_5_2(arg.name)
+                    .with_pos_and_raw(position, raw)
+                    .into());
+            }
+            input_arg
+        } else if let Some(input_arg) = input_kwargs.remove(&arg.name) {
+            input_arg
+        } else if let Some(default) = &arg.default {
+            let default = interpret_expr(default, i_data)?;
+            if arg.type_.accepts(&default.get_type_obj()) {
+                default
             } else {
-                last = interpret_expr(ele, i_data)?;
+                let target = Value::Type(arg.type_.to_owned());
+                default
+                    .bin_opr(&OprType::TypeCast, target.to_owned())
+                    .map_err(|_| {
+                        ZyxtError::error_4_1_0(OprType::TypeCast.to_string(), default, target)
+                            .with_pos_and_raw(position, raw)
+                    })?
             }
-            pop!();
-            return Ok(last);
         } else {
-            last = interpret_expr(ele, i_data)?;
-            if let Value::Return(value) = last {
-                pop!();
-                return if returnable {
-                    Ok(*value)
-                } else {
-                    Ok(Value::Return(value))
-                };
-            }
-        }
+            return Err(ZyxtError::error_2_3(arg.name)
+                .with_pos_and_raw(position, raw)
+                .into());
+        };
+        processed_args.insert(arg.name, input_arg);
+    }
+    if input_args.next().is_some() {
+        return Err(ZyxtError::error_2_5_0(max_args, got_args)
+            .with_pos_and_raw(position, raw)
+            .into());
+    }
+    if let Some(arg) = vkwarg {
+        let mut content = input_kwargs.into_iter().collect::<Vec<_>>();
+        content.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+        processed_args.insert(
+            arg.name.to_owned(),
+            Value::Dict {
+                key_type: Type::from_name("str"),
+                value_type: arg.item_type(),
+                content: content
+                    .into_iter()
+                    .map(|(k, v)| (Value::Str(k), v))
+                    .collect(),
+            },
+        );
+    } else if let Some(name) = input_kwargs.into_keys().min() {
+        return Err(ZyxtError::error_2_5_1(name)
+            .with_pos_and_raw(position, raw)
+            .into());
     }
-    pop!();
-    Ok(last)
+    Ok(processed_args)
 }
 
-pub fn interpret_asts<O: Print>(
-    input: &Vec<Element>,
+/// The types that the generic parameters of a class or struct are given by the arguments it is constructed with
+fn infer_type_args(
+    params: &[Argument],
+    input_args: &[Value],
+    input_kwargs: &HashMap<String, Value>,
+) -> HashMap<String, Type> {
+    Type::infer_generic_args(
+        &params.iter().map(Param::from).collect::<Vec<_>>(),
+        &input_args
+            .iter()
+            .map(Value::get_type_obj)
+            .collect::<Vec<_>>(),
+        &input_kwargs
+            .iter()
+            .map(|(name, kwarg)| (name.to_owned(), kwarg.get_type_obj()))
+            .collect(),
+    )
+}
+
+/// Calls a value, passing `self_` as the first argument if it is given.
+/// The value of `self_` at the end of the call is returned along with the result
+fn call_with_self<O: Print>(
+    to_call: Value,
+    self_: Option<Value>,
+    input_args: Vec<Value>,
+    input_kwargs: HashMap<String, Value>,
     i_data: &mut InterpreterData<Value, O>,
-) -> Result<i32, ZyxtError> {
-    let mut last = Value::Null;
-    for ele in input {
-        if let Element::Return {
-            value,
+    position: &Position,
+    raw: &String,
+) -> Result<(Value, Option<Value>), ControlFlow> {
+    if let Value::Proc {
+        is_fn,
+        args,
+        return_type,
+        content,
+        label,
+        environment,
+    } = to_call
+    {
+        // the instance is only passed to procedures that take `self`
+        let self_ = self_.filter(|_| args.first().is_some_and(|arg| arg.name == "self"));
+        let self_name = self_
+            .as_ref()
+            .and_then(|_| args.first())
+            .map(|arg| arg.name.to_owned());
+        let skipped = self_.is_some() as usize;
+        let input_args = self_.into_iter().chain(input_args).collect();
+        // defaults are evaluated where the procedure was defined, like its body
+        let caller_heap = std::mem::replace(&mut i_data.heap, environment.0.to_owned());
+        let processed_args = bind_args(
+            args,
+            input_args,
+            input_kwargs,
+            skipped,
+            i_data,
             position,
             raw,
+        );
+        i_data.heap = caller_heap;
+        let processed_args = processed_args?;
+
+        if i_data.call_depth >= i_data.max_call_depth {
+            return Err(ZyxtError::error_0_2(i_data.max_call_depth)
+                .with_pos_and_raw(position, raw)
+                .into());
+        }
+        let new_self;
+        // free variables are resolved from where the procedure was defined, not where it was called
+        let res = if is_fn {
+            let mut fn_i_data = InterpreterData::from_environment(environment, i_data.out);
+            fn_i_data.call_depth = i_data.call_depth + 1;
+            fn_i_data.max_call_depth = i_data.max_call_depth;
+            fn_i_data
+                .heap
+                .last()
+                .unwrap()
+                .borrow_mut()
+                .extend(processed_args);
+            let res = interpret_block(&content, &mut fn_i_data, false);
+            new_self = fn_i_data.get_self(&self_name);
+            fn_i_data.pop_frame().and(res)
+        } else {
+            // frames that are read-only to the caller stay read-only to the procedure
+            let read_only_depth = environment
+                .0
+                .iter()
+                .zip(&i_data.heap[..i_data.read_only_depth])
+                .take_while(|(a, b)| Rc::ptr_eq(a, b))
+                .count();
+            let caller_heap = std::mem::replace(&mut i_data.heap, environment.0);
+            let caller_read_only_depth =
+                std::mem::replace(&mut i_data.read_only_depth, read_only_depth);
+            i_data.add_frame(Some(FrameData {
+                position: position.to_owned(),
+                raw_call: raw.to_owned(),
+                args: processed_args.to_owned(),
+            }));
+            i_data
+                .heap
+                .last()
+                .unwrap()
+                .borrow_mut()
+                .extend(processed_args);
+            i_data.call_depth += 1;
+            let res = interpret_block(&content, i_data, false);
+            new_self = i_data.get_self(&self_name);
+            let res = i_data.pop_frame().and(res);
+            i_data.call_depth -= 1;
+            i_data.heap = caller_heap;
+            i_data.read_only_depth = caller_read_only_depth;
+            res
+        };
+        match res {
+            Err(ControlFlow::Return {
+                label: target,
+                value,
+            }) if target.is_none() || target == label => Ok(*value),
+            // loops can't be broken out of from inside a procedure
+            Err(flow @ (ControlFlow::Break { .. } | ControlFlow::Continue { .. })) => {
+                Err(flow.into_error().with_pos_and_raw(position, raw).into())
+            }
+            // procedures returning `T!?` return the errors raised in them
+            Err(ControlFlow::Error(e)) if matches!(return_type, Type::Errorable(_)) => {
+                Ok(Value::Error(e))
+            }
+            res => res,
+        }
+        .map(|res| (res, new_self))
+    } else if let Value::Overload(procs) = &to_call {
+        // the overload is chosen by the types of the arguments when they were not known beforehand
+        let arg_types = input_args
+            .iter()
+            .map(Value::get_type_obj)
+            .collect::<Vec<_>>();
+        let kwarg_types = input_kwargs
+            .iter()
+            .map(|(name, kwarg)| (name.to_owned(), kwarg.get_type_obj()))
+            .collect::<HashMap<_, _>>();
+        let candidates = procs
+            .iter()
+            .filter_map(|proc| {
+                if let Type::Proc { mut params, .. } = proc.get_type_obj() {
+                    if self_.is_some() && takes_self(proc) {
+                        params.remove(0);
+                    }
+                    let bindings = Type::infer_generic_args(&params, &arg_types, &kwarg_types);
+                    let params = params
+                        .iter()
+                        .map(|param| param.substitute(&bindings, Some(&Type::any())))
+                        .collect::<Vec<_>>();
+                    Some((proc, params))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        let exact = candidates
+            .iter()
+            .find(|(_, params)| Type::params_accept(params, &arg_types, &kwarg_types));
+        let (proc, input_args, input_kwargs) = if let Some((proc, _)) = exact {
+            ((*proc).to_owned(), input_args, input_kwargs)
+        } else {
+            // otherwise numbers are converted, as they are when the overload is chosen beforehand
+            let mut accepting = candidates
+                .iter()
+                .filter_map(|(proc, params)| {
+                    let (args, kwargs) = coerce_num_args(params, &input_args, &input_kwargs);
+                    let arg_types = args.iter().map(Value::get_type_obj).collect::<Vec<_>>();
+                    let kwarg_types = kwargs
+                        .iter()
+                        .map(|(name, kwarg)| (name.to_owned(), kwarg.get_type_obj()))
+                        .collect::<HashMap<_, _>>();
+                    Type::params_accept(params, &arg_types, &kwarg_types)
+                        .then(|| ((*proc).to_owned(), args, kwargs))
+                })
+                .collect::<Vec<_>>();
+            let all_arg_types = arg_types
+                .into_iter()
+                .chain(kwarg_types.into_values())
+                .collect::<Vec<_>>();
+            match accepting.len() {
+                0 => {
+                    return Err(ZyxtError::error_4_13_0(all_arg_types)
+                        .with_pos_and_raw(position, raw)
+                        .into())
+                }
+                1 => accepting.remove(0),
+                _ => {
+                    return Err(ZyxtError::error_4_13_1(all_arg_types)
+                        .with_pos_and_raw(position, raw)
+                        .into())
+                }
+            }
+        };
+        call_with_self(proc, self_, input_args, input_kwargs, i_data, position, raw)
+    } else if let Value::Type(
+        definition @ Type::Definition {
+            is_struct: true,
+            fields,
             ..
-        } = ele
+        },
+    ) = &to_call
+    {
+        let bindings = infer_type_args(fields, &input_args, &input_kwargs);
+        // the fields of a struct are filled in like the arguments of a procedure
+        let attrs = bind_args(
+            fields.to_owned(),
+            input_args,
+            input_kwargs,
+            0,
+            i_data,
+            position,
+            raw,
+        )?;
+        Ok((
+            Value::ClassInstance {
+                type_: definition.generic_instance_type(&bindings),
+                attrs,
+            },
+            None,
+        ))
+    } else if let Value::Type(definition @ Type::Definition { .. }) = &to_call {
+        let mut attrs = HashMap::new();
+        for (name, attr) in definition.all_inst_attrs() {
+            attrs.insert(name, interpret_expr(&attr, i_data)?);
+        }
+        let bindings = match definition.get_attrs().get("_init") {
+            Some(Element::Procedure { args, .. }) => infer_type_args(
+                &args[args.first().is_some_and(|arg| arg.name == "self") as usize..],
+                &input_args,
+                &input_kwargs,
+            ),
+            _ => HashMap::new(),
+        };
+        let instance = Value::ClassInstance {
+            type_: definition.generic_instance_type(&bindings),
+            attrs,
+        };
+        if let Some(init) = definition.get_attrs().get("_init") {
+            let init = interpret_expr(init, i_data)?;
+            let (_, new_instance) = call_with_self(
+                init,
+                Some(instance.to_owned()),
+                input_args,
+                input_kwargs,
+                i_data,
+                position,
+                raw,
+            )?;
+            Ok((new_instance.unwrap_or(instance), None))
+        } else if let Some(name) = input_kwargs.into_keys().min() {
+            Err(ZyxtError::error_2_5_1(name)
+                .with_pos_and_raw(position, raw)
+                .into())
+        } else if !input_args.is_empty() {
+            Err(ZyxtError::error_2_5_0(0, input_args.len())
+                .with_pos_and_raw(position, raw)
+                .into())
+        } else {
+            Ok((instance, None))
+        }
+    } else if let Value::EnumVariant {
+        type_,
+        variant,
+        value,
+    } = &to_call
+    {
+        // a struct variant is constructed by its struct, then wrapped in the variant
+        if let Value::Type(Type::Definition {
+            is_struct: true, ..
+        }) = **value
         {
-            let mut return_val = interpret_expr(value, i_data)?;
-            let res = i_data.pop_frame()?;
-            if let Some(res) = res {
-                return_val = res;
+            let (instance, _) = call_with_self(
+                *value.to_owned(),
+                None,
+                input_args,
+                input_kwargs,
+                i_data,
+                position,
+                raw,
+            )?;
+            Ok((
+                Value::EnumVariant {
+                    type_: type_.to_owned(),
+                    variant: variant.to_owned(),
+                    value: Box::new(instance),
+                },
+                None,
+            ))
+        } else {
+            Err(
+                ZyxtError::error_3_1_1(to_call.to_owned(), "_call".to_string())
+                    .with_pos_and_raw(position, raw)
+                    .into(),
+            )
+        }
+    } else if let Value::ClassInstance { type_, .. } = &to_call {
+        if let Some(call) = type_.get_attrs().get("_call") {
+            let call = interpret_expr(call, i_data)?;
+            call_with_self(
+                call,
+                Some(to_call),
+                input_args,
+                input_kwargs,
+                i_data,
+                position,
+                raw,
+            )
+        } else {
+            Err(ZyxtError::error_3_1_1(to_call, "_call".to_string())
+                .with_pos_and_raw(position, raw)
+                .into())
+        }
+    } else if let Ok(v) = to_call.call(input_args) {
+        Ok((v, None))
+    } else {
+        Err(ZyxtError::error_3_1_1(to_call, "_call".to_string())
+            .with_pos_and_raw(position, raw)
+            .into())
+    }
+}
+
+/// Assigns a value to a variable, or to an item of a vector or an attribute of an instance stored in a variable
+fn assign<O: Print>(
+    variable: &Element,
+    value: Value,
+    i_data: &mut InterpreterData<Value, O>,
+    position: &Position,
+    raw: &String,
+) -> Result<(), ControlFlow> {
+    if let Element::Index { parent, index, .. } = variable {
+        let mut seq = interpret_expr(parent, i_data)?;
+        let index = interpret_expr(index, i_data)?;
+        seq::set_index(&mut seq, &index, value, position, raw)?;
+        assign(parent, seq, i_data, position, raw)
+    } else if let Element::UnaryOpr {
+        type_: OprType::Deref,
+        operand,
+        ..
+    } = variable
+    {
+        match interpret_expr(operand, i_data)? {
+            Value::Ref {
+                is_pointer: true,
+                slot,
+                ..
+            } => {
+                slot.set(value);
+                Ok(())
             }
-            return if let Value::I32(v) = return_val {
-                Ok(v)
-            } else {
-                Err(ZyxtError::error_4_2(return_val).with_pos_and_raw(position, raw))
-            };
+            pointer => Err(ZyxtError::error_4_1_1(OprType::Deref.to_string(), pointer)
+                .with_pos_and_raw(position, raw)
+                .into()),
+        }
+    } else if let Element::Variable { parent, name, .. } = variable {
+        if **parent == Element::NullElement {
+            return Ok(i_data.set_val(name, &value, position, raw)?);
+        }
+        let mut instance = interpret_expr(parent, i_data)?;
+        if let Value::ClassInstance { attrs, type_ } = &mut instance {
+            // structs have only the fields they declare
+            if type_.fields().is_some() && !attrs.contains_key(name) {
+                return Err(ZyxtError::error_3_1_1(instance, name.to_owned())
+                    .with_pos_and_raw(position, raw)
+                    .into());
+            }
+            attrs.insert(name.to_owned(), value);
+            assign(parent, instance, i_data, position, raw)
         } else {
-            last = interpret_expr(ele, i_data)?;
-            if let Value::Return(mut value) = last {
-                let res = i_data.pop_frame()?;
-                if let Some(res) = res {
-                    value = Box::new(res);
+            Err(ZyxtError::error_3_1_1(instance, name.to_owned())
+                .with_pos_and_raw(position, raw)
+                .into())
+        }
+    } else {
+        Ok(i_data.set_val(&variable.get_name(), &value, position, raw)?)
+    }
+}
+
+/// The variable `is` compares by: the one a reference or pointer refers to, or else the variable itself.
+/// Other values are never the same as anything
+fn identity<O: Print>(
+    operand: &Element,
+    i_data: &mut InterpreterData<Value, O>,
+) -> Result<Option<Slot>, ControlFlow> {
+    Ok(match interpret_expr(operand, i_data)? {
+        Value::Ref { slot, .. } => Some(slot),
+        _ => match operand {
+            Element::Variable {
+                name,
+                parent,
+                position,
+                raw,
+            } if **parent == Element::NullElement => Some(i_data.get_slot(name, position, raw)?),
+            _ => None,
+        },
+    })
+}
+
+fn match_pattern<O: Print>(
+    pattern: &Pattern,
+    value: &Value,
+    i_data: &mut InterpreterData<Value, O>,
+    bindings: &mut HashMap<String, Value>,
+) -> Result<bool, ControlFlow> {
+    Ok(match pattern {
+        Pattern::Wildcard => true,
+        Pattern::Binding(name) => {
+            bindings.insert(name.to_owned(), value.to_owned());
+            true
+        }
+        Pattern::Value(expected) => matches!(
+            value.bin_opr(&OprType::Eq, interpret_expr(expected, i_data)?),
+            Ok(Value::Bool(true))
+        ),
+        Pattern::Type(type_) => is_of_type(value, interpret_expr(type_, i_data)?),
+        Pattern::Destructure { type_, fields } => {
+            let type_ = interpret_expr(type_, i_data)?;
+            // struct variants match values of the same variant, whose structs are then destructured
+            let value = match (&type_, value) {
+                (
+                    Value::EnumVariant {
+                        type_: enum_type,
+                        variant,
+                        ..
+                    },
+                    Value::EnumVariant {
+                        type_: value_type,
+                        variant: value_variant,
+                        value,
+                    },
+                ) if enum_type == value_type && variant == value_variant => &**value,
+                _ if is_of_type(value, type_.to_owned()) => value,
+                _ => return Ok(false),
+            };
+            if let Value::ClassInstance { attrs, .. } = value {
+                for (name, field) in fields {
+                    match attrs.get(name) {
+                        Some(attr) if match_pattern(field, attr, i_data, bindings)? => (),
+                        _ => return Ok(false),
+                    }
                 }
-                return if let Value::I32(v) = *value {
-                    Ok(v)
-                } else {
-                    Err(ZyxtError::error_4_2(*value)
-                        .with_pos_and_raw(ele.get_pos(), &ele.get_raw()))
-                };
+                true
+            } else {
+                false
             }
         }
+    })
+}
+
+fn is_of_type(value: &Value, type_: Value) -> bool {
+    if let Value::Type(type_) = type_ {
+        type_ == Type::any()
+            || value.get_type_obj() == type_
+            // instances of classes, structs and enums, including of classes inheriting from them
+            || (type_.is_user_defined() && type_.instance_type().accepts(&value.get_type_obj()))
+    } else {
+        false
     }
-    let res = i_data.pop_frame()?;
-    if let Some(res) = res {
-        last = res;
+}
+
+/// Decides whether a loop runs again after its body finishes, handling any `break` or `continue`
+/// aimed at it
+fn continues_loop(
+    res: Result<Value, ControlFlow>,
+    label: &Option<String>,
+) -> Result<bool, ControlFlow> {
+    match res {
+        Ok(_) => Ok(true),
+        Err(flow @ ControlFlow::Continue { .. }) if flow.targets(label) => Ok(true),
+        Err(flow @ ControlFlow::Break { .. }) if flow.targets(label) => Ok(false),
+        Err(flow) => Err(flow),
     }
-    return if let Value::I32(v) = last {
-        Ok(v)
-    } else if let Value::Null = last {
-        Ok(0)
-    } else {
-        let last_ele = input.last().unwrap();
-        Err(ZyxtError::error_4_2(last).with_pos_and_raw(last_ele.get_pos(), &last_ele.get_raw()))
+}
+
+fn interpret_c_style_for<O: Print>(
+    init: &Element,
+    condition: &Element,
+    step: &Element,
+    content: &Vec<Element>,
+    label: &Option<String>,
+    i_data: &mut InterpreterData<Value, O>,
+) -> Result<Value, ControlFlow> {
+    interpret_expr(init, i_data)?;
+    loop {
+        if *condition != Element::NullElement
+            && interpret_expr(condition, i_data)? != Value::Bool(true)
+        {
+            break;
+        }
+        if !continues_loop(interpret_block(content, i_data, true), label)? {
+            break;
+        }
+        interpret_expr(step, i_data)?;
+    }
+    Ok(Value::Null)
+}
+
+pub fn interpret_block<O: Print>(
+    input: &Vec<Element>,
+    i_data: &mut InterpreterData<Value, O>,
+    add_frame: bool,
+) -> Result<Value, ControlFlow> {
+    let mut res = Ok(Value::Null);
+    if add_frame {
+        i_data.add_frame(None);
+    }
+    for ele in input {
+        res = interpret_expr(ele, i_data);
+        if res.is_err() {
+            break;
+        }
+    }
+    if add_frame {
+        res = i_data.pop_frame().and(res);
+    }
+    res
+}
+
+pub fn interpret_asts<O: Print>(
+    input: &Vec<Element>,
+    i_data: &mut InterpreterData<Value, O>,
+) -> Result<i32, ZyxtError> {
+    let mut res = Ok(Value::Null);
+    let mut last_ele = &Element::NullElement;
+    for ele in input {
+        last_ele = ele;
+        res = interpret_expr(ele, i_data);
+        if res.is_err() {
+            break;
+        }
+    }
+    let exit_value = match i_data.pop_frame().and(res) {
+        Ok(v) => v,
+        Err(ControlFlow::Return { label: None, value }) => *value,
+        Err(flow) => return Err(flow.into_error()),
     };
+    match exit_value {
+        Value::I32(v) => Ok(v),
+        Value::Null => Ok(0),
+        _ => Err(ZyxtError::error_4_2(exit_value)
+            .with_pos_and_raw(last_ele.get_pos(), &last_ele.get_raw())),
+    }
 }
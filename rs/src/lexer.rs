@@ -228,9 +228,8 @@ fn lex_stage4(input: Vec<Token>) -> Result<Vec<Token>, ZyxtError> {
     let mut out: Vec<Token> = vec![];
 
     let token_entries = side_dependent_token_entries();
-    let mut type_list = token_entries.iter().map(|e| e.from);
     for (i, t) in input.iter().enumerate() {
-        if !type_list.any(|a| a == t.type_) {
+        if !token_entries.iter().any(|e| e.from == t.type_) {
             out.push(t.to_owned());
             continue;
         }
@@ -26,7 +26,7 @@ pub fn compile(
     logger: &mut Logger<impl Print>,
 ) -> Result<Vec<Element>, ZyxtError> {
     if logger.verbosity == 0 {
-        return gen_instructions(parse_token_list(lex(input, filename)?)?, typelist);
+        return gen_instructions(parse_token_list(lex(input, filename)?)?, typelist, logger);
     }
 
     logger.debug(Yellow.bold().paint("Lexing"));
@@ -43,7 +43,7 @@ pub fn compile(
 
     logger.debug(Yellow.bold().paint("\nGenerating instructions"));
     let check_start = Instant::now();
-    let instructions = gen_instructions(parsed, typelist)?;
+    let instructions = gen_instructions(parsed, typelist, logger)?;
     let check_time = check_start.elapsed().as_micros();
     logger.debug(White.dimmed().paint(format!("{:#?}", instructions)));
 
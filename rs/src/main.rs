@@ -5,7 +5,7 @@ use std::io::Read;
 use std::panic;
 use std::process::exit;
 use zyxt::errors::ZyxtError;
-use zyxt::objects::interpreter_data::{InterpreterData, StdIoPrint};
+use zyxt::objects::interpreter_data::{InterpreterData, StdIoPrint, DEFAULT_MAX_CALL_DEPTH};
 use zyxt::objects::logger::Logger;
 use zyxt::repl;
 
@@ -17,6 +17,9 @@ struct Args {
     /// Enables debugging info
     #[clap(short, long, parse(from_occurrences))]
     verbose: u8,
+    /// Sets how many procedure calls deep the interpreter can go
+    #[clap(long, default_value_t = DEFAULT_MAX_CALL_DEPTH)]
+    max_call_depth: usize,
 }
 #[derive(Parser)]
 enum Subcmd {
@@ -33,6 +36,7 @@ struct Run {
 fn main() {
     let args = Args::parse();
     let verbose = args.verbose;
+    let max_call_depth = args.max_call_depth;
     let mut logger = Logger {
         verbosity: verbose,
         out: &mut StdIoPrint
@@ -62,6 +66,8 @@ fn main() {
             let mut sip2 = StdIoPrint;
             let mut typelist = InterpreterData::default_type(&mut sip1);
             let mut i_data = InterpreterData::default_variable(&mut sip2);
+            typelist.max_call_depth = max_call_depth;
+            i_data.max_call_depth = max_call_depth;
             let exit_code = zyxt::interpret(
                 &zyxt::compile(content, filename, &mut typelist, &mut logger)
                     .unwrap_or_else(|e| e.print_exit(&mut StdIoPrint)),
@@ -72,6 +78,6 @@ fn main() {
             exit(exit_code);
         }
         // TODO Compile, Interpret
-        Subcmd::Repl => repl::repl(verbose),
+        Subcmd::Repl => repl::repl(verbose, max_call_depth),
     }
 }
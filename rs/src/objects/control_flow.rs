@@ -0,0 +1,51 @@
+use crate::errors::ZyxtError;
+use crate::objects::value::Value;
+
+/// Signals that stop the evaluation of an expression early.
+///
+/// These travel through the `Err` side of the interpreter's results until they reach the loop,
+/// procedure or block that handles them; runtime errors travel the same way.
+#[derive(Clone)]
+pub enum ControlFlow {
+    Return {
+        label: Option<String>,
+        value: Box<Value>,
+    },
+    Break {
+        label: Option<String>,
+    },
+    Continue {
+        label: Option<String>,
+    },
+    Error(ZyxtError),
+}
+impl ControlFlow {
+    /// Whether a loop, procedure or block with the label `label` should handle this signal
+    pub fn targets(&self, label: &Option<String>) -> bool {
+        match self {
+            ControlFlow::Return { label: target, .. }
+            | ControlFlow::Break { label: target }
+            | ControlFlow::Continue { label: target } => target.is_none() || target == label,
+            ControlFlow::Error(_) => false,
+        }
+    }
+    /// Converts a signal that nothing handled into an error
+    pub fn into_error(self) -> ZyxtError {
+        match self {
+            ControlFlow::Error(e) => e,
+            ControlFlow::Return {
+                label: Some(label), ..
+            }
+            | ControlFlow::Break { label: Some(label) }
+            | ControlFlow::Continue { label: Some(label) } => ZyxtError::error_3_2(label),
+            ControlFlow::Return { label: None, .. } => ZyxtError::error_2_4("ret".to_string()),
+            ControlFlow::Break { label: None } => ZyxtError::error_2_4("break".to_string()),
+            ControlFlow::Continue { label: None } => ZyxtError::error_2_4("continue".to_string()),
+        }
+    }
+}
+impl From<ZyxtError> for ControlFlow {
+    fn from(e: ZyxtError) -> Self {
+        ControlFlow::Error(e)
+    }
+}
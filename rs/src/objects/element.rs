@@ -1,12 +1,16 @@
 use crate::errors::ZyxtError;
 use crate::gen_instructions;
 use crate::interpreter::interpret_block;
-use crate::objects::interpreter_data::{InterpreterData, Print};
+use crate::objects::control_flow::ControlFlow;
+use crate::objects::interpreter_data::{InterpreterData, LabelData, LabelKind, Print};
+use crate::objects::logger::Logger;
 use crate::objects::position::Position;
 use crate::objects::token::{Flag, OprType, Token};
-use crate::objects::typeobj::Type;
+use crate::objects::typeobj::{Param, Type};
+use crate::objects::value::pow;
 use crate::objects::value::utils::OprError;
 use crate::objects::value::Value;
+use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
@@ -16,6 +20,24 @@ pub struct Condition {
     pub if_true: Vec<Element>,
 }
 #[derive(Clone, PartialEq, Debug)]
+pub enum Pattern {
+    Wildcard,
+    Binding(String),
+    Value(Element),
+    Type(Element),
+    Destructure {
+        type_: Element,
+        fields: Vec<(String, Pattern)>,
+    },
+}
+#[derive(Clone, PartialEq, Debug)]
+pub struct MatchArm {
+    pub position: Position,
+    pub raw: String, // `of` / `else` and the pattern
+    pub pattern: Pattern,
+    pub content: Vec<Element>,
+}
+#[derive(Clone, PartialEq, Debug)]
 pub struct Argument {
     pub name: String,
     pub type_: Type,
@@ -75,11 +97,83 @@ pub enum Element {
         name: String,
         parent: Box<Element>,
     },
+    NullSafe {
+        position: Position,
+        raw: String,
+        operand: Box<Element>, // the parent of an attribute accessed with `?.`
+    },
+    Array {
+        position: Position,
+        raw: String,
+        is_vec: bool,
+        content: Vec<Element>,
+    },
+    Tuple {
+        position: Position,
+        raw: String,
+        content: Vec<Element>,
+    },
+    SetLiteral {
+        position: Position,
+        raw: String,
+        is_frozen: bool,
+        content: Vec<Element>,
+    },
+    Dict {
+        position: Position,
+        raw: String,
+        content: Vec<(Element, Element)>,
+    },
+    Index {
+        position: Position,
+        raw: String,
+        parent: Box<Element>,
+        index: Box<Element>,
+    },
+    Slice {
+        position: Position,
+        raw: String,
+        parent: Box<Element>,
+        start: Box<Element>, // NullElement if open
+        end: Box<Element>,   // NullElement if open
+    },
     If {
         position: Position,
         raw: String,
         conditions: Vec<Condition>,
     },
+    Match {
+        position: Position,
+        raw: String,
+        subject: Box<Element>,
+        arms: Vec<MatchArm>,
+    },
+    While {
+        position: Position,
+        raw: String,
+        condition: Box<Element>,
+        content: Vec<Element>,
+        do_first: bool,
+        label: Option<String>,
+    },
+    For {
+        position: Position,
+        raw: String,
+        variable: Box<Element>, // variable
+        type_: Type,            // variable
+        iterable: Box<Element>,
+        content: Vec<Element>,
+        label: Option<String>,
+    },
+    CStyleFor {
+        position: Position,
+        raw: String,
+        init: Box<Element>,
+        condition: Box<Element>,
+        step: Box<Element>,
+        content: Vec<Element>,
+        label: Option<String>,
+    },
     Block {
         position: Position,
         raw: String,
@@ -94,14 +188,32 @@ pub enum Element {
         position: Position,
         raw: String,
         value: Box<Element>,
+        label: Option<String>,
+    },
+    Break {
+        position: Position,
+        raw: String,
+        label: Option<String>,
+    },
+    Continue {
+        position: Position,
+        raw: String,
+        label: Option<String>,
     },
     Procedure {
         position: Position,
         raw: String,
         is_fn: bool,
+        generics: Vec<Argument>,
         args: Vec<Argument>,
         return_type: Type,
         content: Vec<Element>,
+        label: Option<String>,
+    },
+    Overload {
+        position: Position,
+        raw: String,
+        procs: Vec<Element>, // the procedures, one for each set of parameters
     },
     Preprocess {
         position: Position,
@@ -116,15 +228,128 @@ pub enum Element {
     Class {
         position: Position,
         raw: String,
+        name: Option<String>,
         is_struct: bool,
+        is_mixin: bool,
+        generics: Vec<Argument>,
         class_attrs: HashMap<String, Element>,
         inst_attrs: HashMap<String, Element>,
         content: Vec<Element>,
         args: Option<Vec<Argument>>,
     },
+    Inherit {
+        position: Position,
+        raw: String,
+        parent: Box<Element>,
+    },
+    Enum {
+        position: Position,
+        raw: String,
+        name: Option<String>,
+        variants: IndexMap<String, Element>,
+    },
     NullElement,
     Token(Token),
 }
+impl Pattern {
+    /// Checks the pattern against the type of the value being matched, declaring its bindings.
+    /// Returns whether the pattern can match at all, and whether it matches every value
+    pub fn eval_type<O: Print, L: Print>(
+        &mut self,
+        subject_type: &Type,
+        typelist: &mut InterpreterData<Type, O>,
+        logger: &mut Logger<L>,
+    ) -> Result<(bool, bool), ZyxtError> {
+        match self {
+            Pattern::Wildcard => Ok((true, true)),
+            Pattern::Binding(name) => {
+                typelist.declare_val(name, subject_type);
+                Ok((true, true))
+            }
+            Pattern::Value(value) => {
+                let value_type = value.eval_type(typelist, logger)?;
+                if value_type == Type::from_name("type")
+                    || matches!(value_type, Type::Definition { .. } | Type::Enum { .. })
+                {
+                    *self = Pattern::Type(value.to_owned());
+                    return self.eval_type(subject_type, typelist, logger);
+                }
+                Ok((
+                    *subject_type == Type::any()
+                        || value_type == Type::any()
+                        || value_type == *subject_type
+                        || (value_type.is_num() && subject_type.is_num()),
+                    false,
+                ))
+            }
+            Pattern::Type(type_) => {
+                if type_.eval_type(typelist, logger)? != Type::from_name("type")
+                    || !matches!(type_, Element::Variable { .. })
+                {
+                    return Ok((true, false));
+                }
+                let type_ = type_.as_type();
+                if type_ == Type::any() || type_ == *subject_type {
+                    Ok((true, true))
+                } else {
+                    Ok((*subject_type == Type::any(), false))
+                }
+            }
+            Pattern::Destructure { type_, fields } => {
+                let type_type = type_.eval_type(typelist, logger)?;
+                let instance_type = type_type.instance_type();
+                for (name, field) in fields.iter_mut() {
+                    let field_type = match &type_type {
+                        // struct variants of enums have the fields they are constructed from
+                        Type::Proc {
+                            params,
+                            return_type,
+                            ..
+                        } if matches!(
+                            &**return_type,
+                            Type::Instance { implementation: Some(implementation), .. }
+                                if matches!(**implementation, Type::Enum { .. })
+                        ) =>
+                        {
+                            params
+                                .iter()
+                                .find(|param| param.name == *name)
+                                .map(|param| param.type_.to_owned())
+                                .ok_or_else(|| {
+                                    ZyxtError::error_3_1_0(
+                                        type_.to_owned(),
+                                        type_type.to_owned(),
+                                        name.to_owned(),
+                                    )
+                                    .with_element(type_)
+                                })?
+                        }
+                        _ => Element::attr_type(type_, &instance_type, name, typelist, logger)?,
+                    };
+                    // a lone name binds the field, unless it names a type
+                    if let Pattern::Value(Element::Variable {
+                        name,
+                        parent,
+                        position,
+                        raw,
+                    }) = field
+                    {
+                        let names_type = matches!(
+                            typelist.get_val(name, position, raw),
+                            Ok(t) if t == Type::from_name("type")
+                                || matches!(t, Type::Definition { .. } | Type::Enum { .. })
+                        );
+                        if **parent == Element::NullElement && !names_type {
+                            *field = Pattern::Binding(name.to_owned());
+                        }
+                    }
+                    field.eval_type(&field_type, typelist, logger)?;
+                }
+                Ok((true, false))
+            }
+        }
+    }
+}
 impl Display for Argument {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -144,6 +369,31 @@ impl Display for Argument {
         )
     }
 }
+impl Argument {
+    pub fn is_varg(&self) -> bool {
+        self.type_.is_varg()
+    }
+    pub fn is_vkwarg(&self) -> bool {
+        self.type_.is_vkwarg()
+    }
+    pub fn item_type(&self) -> Type {
+        self.type_.variadic_item_type()
+    }
+    /// The type of the argument inside the procedure.
+    /// Variable arguments are an `array<T>`, variable keyword arguments a `dict<str, T>`,
+    /// and `#no_inherited<T>` arguments a `T`
+    pub fn value_type(&self) -> Type {
+        if self.is_varg() {
+            Type::sequence(self.item_type(), false)
+        } else if self.is_vkwarg() {
+            Type::generic("dict", vec![Type::from_name("str"), self.item_type()])
+        } else if matches!(&self.type_, Type::Instance { name, .. } if name == "#no_inherited") {
+            self.item_type()
+        } else {
+            self.type_.to_owned()
+        }
+    }
+}
 pub trait VecElementRaw {
     fn get_raw(&self) -> String;
 }
@@ -162,6 +412,7 @@ impl Element {
             Element::NullElement => panic!("null element"),
             Element::Token(Token { position, .. })
             | Element::Variable { position, .. }
+            | Element::NullSafe { position, .. }
             | Element::Literal { position, .. }
             | Element::Comment { position, .. }
             | Element::Call { position, .. }
@@ -169,14 +420,29 @@ impl Element {
             | Element::BinaryOpr { position, .. }
             | Element::Declare { position, .. }
             | Element::Set { position, .. }
+            | Element::Array { position, .. }
+            | Element::Tuple { position, .. }
+            | Element::SetLiteral { position, .. }
+            | Element::Dict { position, .. }
+            | Element::Index { position, .. }
+            | Element::Slice { position, .. }
             | Element::If { position, .. }
+            | Element::Match { position, .. }
+            | Element::While { position, .. }
+            | Element::For { position, .. }
+            | Element::CStyleFor { position, .. }
             | Element::Block { position, .. }
             | Element::Delete { position, .. }
             | Element::Return { position, .. }
+            | Element::Break { position, .. }
+            | Element::Continue { position, .. }
             | Element::Procedure { position, .. }
+            | Element::Overload { position, .. }
             | Element::Preprocess { position, .. }
             | Element::Defer { position, .. }
-            | Element::Class { position, .. } => position,
+            | Element::Class { position, .. }
+            | Element::Inherit { position, .. }
+            | Element::Enum { position, .. } => position,
         }
     }
     pub fn get_raw(&self) -> String {
@@ -184,6 +450,7 @@ impl Element {
             Element::NullElement => "".to_string(),
             Element::Token(t) => t.get_raw(),
             Element::Variable { raw, .. }
+            | Element::NullSafe { raw, .. }
             | Element::Literal { raw, .. }
             | Element::Comment { raw, .. }
             | Element::Call { raw, .. }
@@ -191,14 +458,43 @@ impl Element {
             | Element::BinaryOpr { raw, .. }
             | Element::Declare { raw, .. }
             | Element::Set { raw, .. }
+            | Element::Array { raw, .. }
+            | Element::Tuple { raw, .. }
+            | Element::SetLiteral { raw, .. }
+            | Element::Dict { raw, .. }
+            | Element::Index { raw, .. }
+            | Element::Slice { raw, .. }
             | Element::If { raw, .. }
+            | Element::Match { raw, .. }
+            | Element::While { raw, .. }
+            | Element::For { raw, .. }
+            | Element::CStyleFor { raw, .. }
             | Element::Block { raw, .. }
             | Element::Delete { raw, .. }
             | Element::Return { raw, .. }
+            | Element::Break { raw, .. }
+            | Element::Continue { raw, .. }
             | Element::Procedure { raw, .. }
+            | Element::Overload { raw, .. }
             | Element::Preprocess { raw, .. }
             | Element::Defer { raw, .. }
-            | Element::Class { raw, .. } => raw.to_owned(),
+            | Element::Class { raw, .. }
+            | Element::Inherit { raw, .. }
+            | Element::Enum { raw, .. } => raw.to_owned(),
+        }
+    }
+    /// The type of a procedure, from its signature
+    pub fn proc_type(&self) -> Option<Type> {
+        if let Element::Procedure {
+            is_fn,
+            args,
+            return_type,
+            ..
+        } = self
+        {
+            Some(Type::proc(*is_fn, args, return_type.to_owned()))
+        } else {
+            None
         }
     }
     pub fn get_name(&self) -> String {
@@ -220,6 +516,38 @@ impl Element {
             panic!("not variable")
         }
     }
+    /// The declared return type of the magic method overloading an operator on the first operand,
+    /// or the reflected one on the second operand of a binary operator, if either is defined
+    fn overload_return_type(type_: &OprType, type1: &Type, type2: Option<&Type>) -> Option<Type> {
+        let return_type = |operand_type: &Type, other: Option<&Type>, name: Option<String>| {
+            if !operand_type.is_user_defined() {
+                return None;
+            }
+            match operand_type.get_attrs().get(&name?) {
+                Some(Element::Procedure {
+                    args, return_type, ..
+                }) if Element::overload_accepts(args, operand_type, other) => Some(
+                    return_type.substitute(&operand_type.generic_bindings(), Some(&Type::any())),
+                ),
+                Some(Element::Procedure { .. }) | None => None,
+                Some(_) => Some(Type::any()),
+            }
+        };
+        return_type(type1, type2, type_.magic_method().map(String::from)).or_else(|| {
+            type2.and_then(|type2| return_type(type2, Some(type1), type_.reflected_magic_method()))
+        })
+    }
+    /// Whether a magic method with these parameters can be called with the other operand of an operator
+    pub fn overload_accepts(args: &[Argument], operand_type: &Type, other: Option<&Type>) -> bool {
+        match other {
+            Some(other) => args.get(1).is_some_and(|arg| {
+                arg.type_
+                    .substitute(&operand_type.generic_bindings(), Some(&Type::any()))
+                    .accepts(other)
+            }),
+            None => true,
+        }
+    }
     pub fn bin_op_return_type(
         type_: &OprType,
         type1: Type,
@@ -227,8 +555,110 @@ impl Element {
         position: &Position,
         raw: &String,
     ) -> Result<Type, ZyxtError> {
-        if type_ == &OprType::TypeCast {
+        if type_ == &OprType::Elvis {
+            // `x ?: y` is x if it is not null, so it can only be null if y can be
+            let left_type = type1.non_null();
+            return if type2 == Type::null() {
+                Ok(left_type.nullable())
+            } else if left_type.accepts(&type2.non_null()) {
+                Ok(if matches!(type2, Type::Nullable(_)) {
+                    left_type.nullable()
+                } else {
+                    left_type
+                })
+            } else {
+                Err(
+                    ZyxtError::error_4_0_0(type_.to_string(), type1.to_string(), type2.to_string())
+                        .with_pos_and_raw(position, raw),
+                )
+            };
+        }
+        if type_ == &OprType::ErrorHandle {
+            // `x !?: handler` is x, or what the handler returns if x is an error
+            let value_type = type1.non_error();
+            return match type2 {
+                _ if type2 == Type::any() => Ok(Type::any()),
+                Type::Proc {
+                    ref params,
+                    ref return_type,
+                    ..
+                } if Type::params_accept(params, &[Type::from_name("err")], &HashMap::new()) => {
+                    let return_type = return_type.non_error();
+                    if value_type.accepts(&return_type) {
+                        Ok(value_type)
+                    } else if let Some(common_type) = value_type.or_null(&return_type) {
+                        Ok(common_type)
+                    } else {
+                        Err(ZyxtError::error_4_0_0(
+                            type_.to_string(),
+                            type1.to_string(),
+                            type2.to_string(),
+                        )
+                        .with_pos_and_raw(position, raw))
+                    }
+                }
+                _ => Err(ZyxtError::error_4_15_1(type2).with_pos_and_raw(position, raw)),
+            };
+        }
+        if let Some(errorable) = [&type1, &type2]
+            .into_iter()
+            .find(|type_| matches!(type_, Type::Errorable(_)))
+        {
+            return Err(
+                ZyxtError::error_4_15_0(errorable.to_owned()).with_pos_and_raw(position, raw)
+            );
+        }
+        // a value that may be null can only be compared, until it is checked not to be null
+        if ![
+            OprType::Eq,
+            OprType::Noteq,
+            OprType::Iseq,
+            OprType::Isnteq,
+            OprType::Istype,
+            OprType::Isnttype,
+            OprType::Is,
+            OprType::Isnt,
+        ]
+        .contains(type_)
+        {
+            if let Some(nullable) = [&type1, &type2]
+                .into_iter()
+                .find(|type_| matches!(type_, Type::Nullable(_)))
+            {
+                return Err(
+                    ZyxtError::error_4_14_0(nullable.to_owned()).with_pos_and_raw(position, raw)
+                );
+            }
+        }
+        if let Some(return_type) = Element::overload_return_type(type_, &type1, Some(&type2)) {
+            return Ok(return_type);
+        } else if type_ == &OprType::TypeCast {
             return Ok(type2);
+        } else if [OprType::And, OprType::Or].contains(type_)
+            && [&type1, &type2].into_iter().any(|type_| {
+                *type_ != Type::from_name("bool")
+                    && *type_ != Type::any()
+                    && !matches!(type_, Type::Generic(_))
+            })
+        {
+            return Err(ZyxtError::error_4_0_0(
+                type_.to_string(),
+                type1.to_string(),
+                type2.to_string(),
+            )
+            .with_pos_and_raw(position, raw));
+        } else if [OprType::Lt, OprType::Lteq, OprType::Gt, OprType::Gteq].contains(type_)
+            && [&type1, &type2]
+                .into_iter()
+                .any(|type_| type_.is_user_defined() || type_.referenced().is_some())
+        {
+            // only instances with the magic method can be ordered
+            return Err(ZyxtError::error_4_0_0(
+                type_.to_string(),
+                type1.to_string(),
+                type2.to_string(),
+            )
+            .with_pos_and_raw(position, raw));
         } else if [
             OprType::Eq,
             OprType::Noteq,
@@ -237,7 +667,9 @@ impl Element {
             OprType::Gt,
             OprType::Gteq,
             OprType::Iseq,
-            OprType::Eq,
+            OprType::Isnteq,
+            OprType::Is,
+            OprType::Isnt,
             OprType::And,
             OprType::Or,
             OprType::Xor,
@@ -245,10 +677,44 @@ impl Element {
         .contains(type_)
         {
             return Ok(Type::from_name("bool"));
+        } else if type1 == Type::any()
+            || type2 == Type::any()
+            || matches!(type1, Type::Generic(_))
+            || matches!(type2, Type::Generic(_))
+        {
+            // nothing is known about the type given to a generic parameter
+            return Ok(Type::any());
+        } else if type1.is_user_defined()
+            || type2.is_user_defined()
+            || type1.referenced().is_some()
+            || type2.referenced().is_some()
+        {
+            return Err(ZyxtError::error_4_0_0(
+                type_.to_string(),
+                type1.to_string(),
+                type2.to_string(),
+            )
+            .with_pos_and_raw(position, raw));
         }
 
-        match Value::default(type1.to_owned())? // TODO
-            .bin_opr(type_, Value::default(type2.to_owned())?)
+        let (value1, mut value2) = (
+            Value::default(type1.to_owned())?,
+            Value::default(type2.to_owned())?,
+        );
+        // a number on the right is one rather than zero, which dividing by is undefined
+        if value2.is_num() {
+            if let Ok(one) =
+                Value::Bool(true).bin_opr(&OprType::TypeCast, Value::Type(type2.to_owned()))
+            {
+                value2 = one;
+            }
+        }
+        if [OprType::Root, OprType::Logarithm].contains(type_) && value1.is_num() && value2.is_num()
+        {
+            // the default values are zero, which neither operator is defined for
+            return Ok(pow::float_type(&type1, &type2));
+        }
+        match value1.bin_opr(type_, value2) // TODO
         {
             Ok(v) => Ok(v.get_type_obj()),
             Err(OprError::NoImplForOpr) => {
@@ -257,7 +723,9 @@ impl Element {
                         .with_pos_and_raw(position, raw),
                 )
             }
-            Err(OprError::TypecastError(ty)) => Ok(ty),
+            Err(OprError::TypecastError(ty)) => Ok(*ty),
+            // the operator is defined for the types, but the result type depends on the actual values
+            Err(OprError::ValueError(_)) => Ok(Type::any()),
         }
     }
     pub fn un_op_return_type(
@@ -266,8 +734,28 @@ impl Element {
         position: &Position,
         raw: &String,
     ) -> Result<Type, ZyxtError> {
-        if type_ == &OprType::Not {
+        if type_ == &OprType::Assert {
+            return Ok(if let Type::Errorable(_) = opnd_type {
+                opnd_type.non_error()
+            } else {
+                opnd_type.non_null()
+            });
+        } else if let Type::Errorable(_) = opnd_type {
+            return Err(ZyxtError::error_4_15_0(opnd_type).with_pos_and_raw(position, raw));
+        } else if let Type::Nullable(_) = opnd_type {
+            return Err(ZyxtError::error_4_14_0(opnd_type).with_pos_and_raw(position, raw));
+        }
+        if let Some(return_type) = Element::overload_return_type(type_, &opnd_type, None) {
+            return Ok(return_type);
+        } else if type_ == &OprType::Not {
             return Ok(Type::from_name("bool"));
+        } else if opnd_type == Type::any() || matches!(opnd_type, Type::Generic(_)) {
+            return Ok(Type::any());
+        } else if opnd_type.is_user_defined() || opnd_type.referenced().is_some() {
+            return Err(
+                ZyxtError::error_4_0_1(type_.to_string(), opnd_type.to_string())
+                    .with_pos_and_raw(position, raw),
+            );
         }
         match Value::default(opnd_type.to_owned())?.un_opr(type_) {
             Ok(v) => Ok(v.get_type_obj()),
@@ -276,125 +764,1045 @@ impl Element {
                 opnd_type.to_string(),
             )
             .with_pos_and_raw(position, raw)),
-            Err(OprError::TypecastError(ty)) => Ok(ty),
+            Err(OprError::TypecastError(ty)) => Ok(*ty),
+            Err(OprError::ValueError(_)) => Ok(Type::any()),
+        }
+    }
+    /// Moves the declarations flagged `hoi` to the top of a block, so that they can be used before them.
+    /// The declarations keep their order, as does everything else
+    pub fn hoist(content: &mut [Element]) {
+        content.sort_by_key(
+            |ele| !matches!(ele, Element::Declare { flags, .. } if flags.contains(&Flag::Hoi)),
+        );
+    }
+    /// Declares the procedures defined in a block before anything in it is checked,
+    /// so that the bodies of procedures can call them before they are declared
+    pub fn declare_procs<O: Print>(content: &[Element], typelist: &mut InterpreterData<Type, O>) {
+        for ele in content {
+            if let Element::Declare {
+                variable,
+                content,
+                type_,
+                ..
+            } = ele
+            {
+                if let Element::Procedure {
+                    is_fn,
+                    args,
+                    return_type,
+                    ..
+                } = &**content
+                {
+                    let proc_type = if *type_ != Type::null() {
+                        type_.to_owned()
+                    } else {
+                        Type::proc(
+                            *is_fn,
+                            args,
+                            // the return type is only known once the body is checked
+                            if *return_type == Type::null() {
+                                Type::any()
+                            } else {
+                                return_type.to_owned()
+                            },
+                        )
+                    };
+                    typelist.predeclare_val(&variable.get_name(), &proc_type);
+                } else if let Element::Overload { procs, .. } = &**content {
+                    let mut procs = procs.to_owned();
+                    for proc in procs.iter_mut() {
+                        if let Element::Procedure { return_type, .. } = proc {
+                            if *return_type == Type::null() {
+                                *return_type = Type::any();
+                            }
+                        }
+                    }
+                    typelist.predeclare_val(&variable.get_name(), &Type::Overload(procs));
+                }
+            }
         }
     }
-    pub fn block_type<O: Print>(
+    pub fn block_type<O: Print, L: Print>(
         content: &mut [Element],
         typelist: &mut InterpreterData<Type, O>,
         add_set: bool,
-    ) -> Result<(Type, Option<Type>), ZyxtError> {
+        logger: &mut Logger<L>,
+    ) -> Result<Type, ZyxtError> {
         let mut last = Type::null();
-        let mut return_type = None;
         if add_set {
             typelist.add_frame(None);
         }
+        Element::hoist(content);
+        Element::declare_procs(content, typelist);
         for ele in content.iter_mut() {
-            last = ele.eval_type(typelist)?;
-            if let Type::Return(value) = last.to_owned() {
-                if return_type.to_owned().is_none() {
-                    return_type = Some(*value);
-                } else if last != return_type.to_owned().unwrap() {
-                    return Err(ZyxtError::error_4_t(last, return_type.unwrap())
-                        .with_pos_and_raw(ele.get_pos(), &ele.get_raw()));
+            let ele_type = ele.eval_type(typelist, logger)?;
+            // anything after a `ret` is unreachable, so the block evaluates to the returned value
+            if !matches!(last, Type::Return(_)) {
+                last = ele_type;
+            }
+        }
+        if add_set {
+            typelist.pop_frame();
+        }
+        if let Type::Return(value) = last {
+            Ok(*value)
+        } else {
+            Ok(last)
+        }
+    }
+    /// Checks that the condition of an `if`, a loop or a `for` header is a bool
+    fn check_condition<O: Print, L: Print>(
+        condition: &mut Element,
+        typelist: &mut InterpreterData<Type, O>,
+        logger: &mut Logger<L>,
+    ) -> Result<(), ZyxtError> {
+        // `loop`, and `for` headers left without a condition, have none to check
+        if *condition == Element::NullElement {
+            return Ok(());
+        }
+        let condition_type = condition.eval_type(typelist, logger)?;
+        if condition_type == Type::from_name("bool")
+            || condition_type == Type::any()
+            || matches!(condition_type, Type::Generic(_))
+        {
+            Ok(())
+        } else {
+            Err(ZyxtError::error_4_17(condition_type).with_element(condition))
+        }
+    }
+    /// Evaluates the type of a block that `ret`, `break` or `continue` can target.
+    /// Returns the type of the last expression, and the type of the values returned from the block
+    pub fn labelled_block_type<O: Print, L: Print>(
+        content: &mut [Element],
+        typelist: &mut InterpreterData<Type, O>,
+        add_set: bool,
+        kind: LabelKind,
+        label: &Option<String>,
+        logger: &mut Logger<L>,
+    ) -> Result<(Type, Option<Type>), ZyxtError> {
+        typelist.labels.push(LabelData {
+            kind,
+            label: label.to_owned(),
+            return_type: None,
+        });
+        let last = Element::block_type(content, typelist, add_set, logger);
+        let return_type = typelist.labels.pop().unwrap().return_type;
+        let last = last?;
+        if let Some(return_type) = return_type {
+            return if let Some(return_type) = return_type.or_null(&last) {
+                Ok((last, Some(return_type)))
+            } else {
+                let last_ele = content
+                    .iter()
+                    .rev()
+                    .find(|ele| **ele != Element::NullElement)
+                    .unwrap();
+                Err(ZyxtError::error_4_t(return_type, last).with_element(last_ele))
+            };
+        }
+        Ok((last, None))
+    }
+    fn loop_label_type<O: Print>(
+        kwd: &str,
+        label: &Option<String>,
+        typelist: &mut InterpreterData<Type, O>,
+        position: &Position,
+        raw: &String,
+    ) -> Result<Type, ZyxtError> {
+        // loops outside of the current procedure can't be targeted
+        for scope in typelist.labels.iter().rev() {
+            match scope.kind {
+                LabelKind::Proc => break,
+                LabelKind::Loop if label.is_none() || scope.label == *label => {
+                    return Ok(Type::null())
                 }
+                _ => (),
             }
         }
-        if let Some(return_type) = return_type.to_owned() {
-            if last != return_type {
-                let last_ele = content.last().unwrap();
-                return Err(ZyxtError::error_4_t(last, return_type)
-                    .with_pos_and_raw(last_ele.get_pos(), &last_ele.get_raw()));
+        Err(if let Some(label) = label {
+            ZyxtError::error_3_2(label.to_owned())
+        } else {
+            ZyxtError::error_2_4(kwd.to_string())
+        }
+        .with_pos_and_raw(position, raw))
+    }
+    /// Evaluates the types of the items of a collection literal, which must all be the same
+    fn common_type<'a, O: Print, L: Print>(
+        items: impl Iterator<Item = &'a mut Element>,
+        typelist: &mut InterpreterData<Type, O>,
+        logger: &mut Logger<L>,
+    ) -> Result<Type, ZyxtError> {
+        let mut common_type: Option<Type> = None;
+        for item in items {
+            let item_type = item.eval_type(typelist, logger)?;
+            if let Some(common_type) = &common_type {
+                if item_type != *common_type {
+                    return Err(
+                        ZyxtError::error_4_7(common_type.to_owned(), item_type).with_element(item)
+                    );
+                }
+            } else {
+                common_type = Some(item_type);
             }
         }
-        if add_set {
-            typelist.pop_frame();
+        Ok(common_type.unwrap_or_else(Type::any))
+    }
+    fn index_type<O: Print, L: Print>(
+        index: &mut Element,
+        typelist: &mut InterpreterData<Type, O>,
+        logger: &mut Logger<L>,
+    ) -> Result<Type, ZyxtError> {
+        let index_type = index.eval_type(typelist, logger)?;
+        if !index_type.is_int() && index_type != Type::any() {
+            return Err(ZyxtError::error_4_8_1(index_type).with_element(index));
+        }
+        Ok(index_type)
+    }
+    /// Checks that a value can be passed to an argument,
+    /// and wraps it in a typecast if it has to be coerced to the argument's type
+    fn coerce_arg(
+        value: &mut Element,
+        value_type: &Type,
+        arg: &str,
+        arg_type: &Type,
+    ) -> Result<(), ZyxtError> {
+        if arg_type.accepts(value_type) {
+            Ok(())
+        } else if arg_type.non_null().is_num() && value_type.is_num() {
+            *value = Element::BinaryOpr {
+                position: value.get_pos().to_owned(),
+                raw: value.get_raw(),
+                type_: OprType::TypeCast,
+                operand1: Box::new(value.to_owned()),
+                operand2: Box::new(arg_type.as_element()),
+            };
+            Ok(())
+        } else {
+            Err(
+                ZyxtError::error_4_9(arg.to_string(), arg_type.to_owned(), value_type.to_owned())
+                    .with_element(value),
+            )
+        }
+    }
+    /// Checks the arguments of a call against the parameters of the procedure called,
+    /// binding them the same way the interpreter does
+    fn check_args(
+        params: &[Param],
+        args: &mut [Element],
+        arg_types: Vec<Type>,
+        kwargs: &mut HashMap<String, Element>,
+        kwarg_types: HashMap<String, Type>,
+        position: &Position,
+        raw: &String,
+    ) -> Result<(), ZyxtError> {
+        let got_args = args.len();
+        let mut args = args.iter_mut().zip(arg_types);
+        let mut kwarg_names = kwargs.keys().cloned().collect::<Vec<_>>();
+        kwarg_names.sort();
+        let mut vkwarg = None;
+        for param in params {
+            if param.type_.is_varg() {
+                for (arg, arg_type) in args.by_ref() {
+                    Element::coerce_arg(
+                        arg,
+                        &arg_type,
+                        &param.name,
+                        &param.type_.variadic_item_type(),
+                    )?;
+                }
+            } else if param.type_.is_vkwarg() {
+                vkwarg = Some(param);
+            } else if let Some((arg, arg_type)) = args.next() {
+                if kwargs.contains_key(&param.name) {
+                    return Err(ZyxtError::error_2_5_2(param.name.to_owned())
+                        .with_pos_and_raw(position, raw));
+                }
+                Element::coerce_arg(arg, &arg_type, &param.name, &param.type_)?;
+            } else if let Some(kwarg) = kwargs.get_mut(&param.name) {
+                kwarg_names.retain(|name| *name != param.name);
+                Element::coerce_arg(kwarg, &kwarg_types[&param.name], &param.name, &param.type_)?;
+            } else if !param.has_default {
+                return Err(
+                    ZyxtError::error_2_3(param.name.to_owned()).with_pos_and_raw(position, raw)
+                );
+            }
+        }
+        if args.next().is_some() {
+            return Err(ZyxtError::error_2_5_0(
+                // variable keyword arguments only take keyword arguments
+                params
+                    .iter()
+                    .filter(|param| !param.type_.is_vkwarg())
+                    .count(),
+                got_args,
+            )
+            .with_pos_and_raw(position, raw));
+        }
+        for name in kwarg_names {
+            if let Some(vkwarg) = vkwarg {
+                Element::coerce_arg(
+                    kwargs.get_mut(&name).unwrap(),
+                    &kwarg_types[&name],
+                    &vkwarg.name,
+                    &vkwarg.type_.variadic_item_type(),
+                )?;
+            } else {
+                return Err(ZyxtError::error_2_5_1(name).with_pos_and_raw(position, raw));
+            }
         }
-        Ok((last, if add_set { None } else { return_type }))
+        Ok(())
     }
-    pub fn call_return_type<O: Print>(
+    pub fn call_return_type<O: Print, L: Print>(
         called: &mut Element,
         args: &mut [Element],
+        kwargs: &mut HashMap<String, Element>,
         typelist: &mut InterpreterData<Type, O>,
+        logger: &mut Logger<L>,
+        position: &Position,
+        raw: &String,
     ) -> Result<Type, ZyxtError> {
-        for arg in args {
-            arg.eval_type(typelist)?;
-        }
+        let arg_types = args
+            .iter_mut()
+            .map(|arg| arg.eval_type(typelist, logger))
+            .collect::<Result<Vec<_>, _>>()?;
+        let kwarg_types = kwargs
+            .iter_mut()
+            .map(|(name, kwarg)| Ok((name.to_owned(), kwarg.eval_type(typelist, logger)?)))
+            .collect::<Result<HashMap<_, _>, ZyxtError>>()?;
         if let Element::Variable {
             ref parent,
             ref name,
             ..
         } = *called
         {
-            if name == &"out".to_string() && parent.get_name() == *"ter" {
+            if name == "out" && matches!(&**parent, Element::Variable { name, .. } if name == "ter")
+            {
                 return Ok(Type::null());
             }
-        }
-        if let Type::Instance {
-            name, type_args, ..
-        } = called.eval_type(typelist)?
-        {
-            if name == *"proc" || name == *"fn" {
-                return Ok(type_args[1].to_owned());
-            }
-        } // TODO type checking for args when arrays are implemented
-        Ok(Type::null())
-        /*if let Some(v) = Variable::default(called.eval_type(typelist)?, typelist)?.call(
-            args.iter_mut().map(|e| Variable::default(e.eval_type(typelist)?, typelist))
-                .collect::<Result<Vec<_>, _>>()?
-        ) { // TODO same as above
-            Ok(v.get_type_obj())
-        } else {
-            Err(ZyxtError::from_pos(called.get_pos())
-                .error_3_1_0(called.to_owned(),
-                             called.eval_type(typelist)?,
-                             "_call".to_string()))
-        }*/
-    }
-    pub fn is_pattern(&self) -> bool {
-        matches!(self, Element::Variable { .. })
-    }
-    pub fn eval_type<O: Print>(
-        &mut self,
-        typelist: &mut InterpreterData<Type, O>,
-    ) -> Result<Type, ZyxtError> {
-        match self {
-            Element::Literal { type_, .. } => Ok(type_.to_owned()),
-            Element::Variable {
-                name,
-                position,
-                raw,
-                ..
-            } => typelist.get_val(name, position, raw),
-            Element::Block { content, .. } => Ok(Element::block_type(content, typelist, true)?.0),
-            Element::Call { called, args, .. } => Element::call_return_type(called, args, typelist),
-            Element::Declare {
-                position,
-                variable,
-                content,
-                flags,
-                type_,
-                raw,
-            } => {
-                if !variable.is_pattern() {
+            // builtins are only called if no variable of the same name is declared
+            if name == "len"
+                && **parent == Element::NullElement
+                && args.len() == 1
+                && !typelist.is_declared(name)
+            {
+                if arg_types[0].item_type().is_none() {
                     return Err(
-                        ZyxtError::error_2_2(*variable.to_owned()).with_element(&**variable)
+                        ZyxtError::error_4_8_0(arg_types[0].to_owned()).with_element(&args[0])
                     );
                 }
-                let content_type = content.eval_type(typelist)?;
-                if *type_ == Type::null() {
-                    typelist.declare_val(&variable.get_name(), &content_type);
-                    *self = Element::Declare {
-                        type_: content_type.to_owned(),
-                        content: content.to_owned(),
-                        variable: variable.to_owned(),
-                        position: position.to_owned(),
-                        raw: raw.to_owned(),
-                        flags: flags.to_owned(),
+                return Ok(Type::from_name("usize"));
+            }
+            // `raise` is given the message of a new error, or an error that was caught
+            if name == "raise"
+                && **parent == Element::NullElement
+                && args.len() == 1
+                && !typelist.is_declared(name)
+            {
+                if ![Type::from_name("str"), Type::from_name("err"), Type::any()]
+                    .contains(&arg_types[0])
+                {
+                    return Err(ZyxtError::error_4_9(
+                        "message".to_string(),
+                        Type::from_name("str"),
+                        arg_types[0].to_owned(),
+                    )
+                    .with_element(&args[0]));
+                }
+                return Ok(Type::null());
+            }
+            if **parent == Element::NullElement {
+                typelist.check_forward_refs(name, position, raw)?;
+            }
+        }
+        let mut is_method = false;
+        let mut is_null_safe = false;
+        let called_type = match called {
+            Element::Variable { parent, name, .. } if **parent != Element::NullElement => {
+                let parent_type;
+                (parent_type, is_null_safe) = Element::parent_type(parent, typelist, logger)?;
+                is_method = matches!(
+                    parent_type,
+                    Type::Instance {
+                        implementation: Some(_),
+                        ..
+                    }
+                );
+                Element::attr_type(parent, &parent_type, name, typelist, logger)?
+            }
+            _ => called.eval_type(typelist, logger)?,
+        };
+        // a method called with `?.` returns null if it is not called
+        let return_type = match called_type {
+            Type::Overload(procs) => {
+                // the overloads that accept the arguments as they are, or else once numbers are converted
+                let mut candidates = vec![];
+                for proc in procs.iter() {
+                    if let Some(Type::Proc {
+                        mut params,
+                        return_type,
+                        ..
+                    }) = proc.proc_type()
+                    {
+                        if is_method && params.first().is_some_and(|param| param.name == "self") {
+                            params.remove(0);
+                        }
+                        let bindings = Type::infer_generic_args(&params, &arg_types, &kwarg_types);
+                        let params = params
+                            .iter()
+                            .map(|param| param.substitute(&bindings, Some(&Type::any())))
+                            .collect::<Vec<_>>();
+                        let return_type = return_type.substitute(&bindings, Some(&Type::any()));
+                        candidates.push((proc, params, return_type));
+                    }
+                }
+                let exact = candidates
+                    .iter()
+                    .filter(|(_, params, _)| Type::params_accept(params, &arg_types, &kwarg_types))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let accepting = if exact.is_empty() {
+                    candidates
+                        .into_iter()
+                        .filter(|(_, params, _)| {
+                            Element::check_args(
+                                params,
+                                &mut args.to_owned(),
+                                arg_types.to_owned(),
+                                &mut kwargs.to_owned(),
+                                kwarg_types.to_owned(),
+                                position,
+                                raw,
+                            )
+                            .is_ok()
+                        })
+                        .collect::<Vec<_>>()
+                } else {
+                    exact
+                };
+                let all_arg_types = arg_types
+                    .iter()
+                    .chain(kwarg_types.values())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                match &*accepting {
+                    [] => Err(ZyxtError::error_4_13_0(all_arg_types)
+                        .with_pos_and_raw(position, raw)
+                        .with_more_elements(&procs)),
+                    [(_, params, return_type)] => {
+                        Element::check_args(
+                            params,
+                            args,
+                            arg_types,
+                            kwargs,
+                            kwarg_types,
+                            position,
+                            raw,
+                        )?;
+                        Ok(return_type.to_owned())
+                    }
+                    // arguments of unknown type are given to the overload that accepts them when called
+                    _ if all_arg_types.contains(&Type::any()) => {
+                        let return_type = accepting[0].2.to_owned();
+                        Ok(
+                            if accepting.iter().all(|(_, _, other)| *other == return_type) {
+                                return_type
+                            } else {
+                                Type::any()
+                            },
+                        )
+                    }
+                    _ => Err(ZyxtError::error_4_13_1(all_arg_types)
+                        .with_pos_and_raw(position, raw)
+                        .with_more_elements(
+                            &accepting
+                                .into_iter()
+                                .map(|(proc, _, _)| proc.to_owned())
+                                .collect::<Vec<_>>(),
+                        )),
+                }
+            }
+            Type::Proc {
+                mut params,
+                return_type,
+                ..
+            } => {
+                // methods called through an instance are passed the instance as `self`
+                if is_method && params.first().is_some_and(|param| param.name == "self") {
+                    params.remove(0);
+                }
+                let bindings = Type::infer_generic_args(&params, &arg_types, &kwarg_types);
+                let params = params
+                    .iter()
+                    .map(|param| param.substitute(&bindings, Some(&Type::any())))
+                    .collect::<Vec<_>>();
+                Element::check_args(&params, args, arg_types, kwargs, kwarg_types, position, raw)?;
+                Ok(return_type.substitute(&bindings, Some(&Type::any())))
+            }
+            Type::Definition { is_mixin: true, .. } => {
+                Err(ZyxtError::error_4_10(called_type).with_pos_and_raw(position, raw))
+            }
+            Type::Definition {
+                is_struct: true,
+                ref fields,
+                ..
+            } => {
+                let params = fields.iter().map(Param::from).collect::<Vec<_>>();
+                let bindings = Type::infer_generic_args(&params, &arg_types, &kwarg_types);
+                let params = params
+                    .iter()
+                    .map(|param| param.substitute(&bindings, Some(&Type::any())))
+                    .collect::<Vec<_>>();
+                Element::check_args(&params, args, arg_types, kwargs, kwarg_types, position, raw)?;
+                Ok(called_type.generic_instance_type(&bindings))
+            }
+            Type::Definition { .. } => {
+                let mut bindings = HashMap::new();
+                if let Some(Element::Procedure { args: params, .. }) =
+                    called_type.get_attrs().get("_init")
+                {
+                    let params = params
+                        .iter()
+                        .skip(params.first().is_some_and(|param| param.name == "self") as usize)
+                        .map(Param::from)
+                        .collect::<Vec<_>>();
+                    bindings = Type::infer_generic_args(&params, &arg_types, &kwarg_types);
+                    let params = params
+                        .iter()
+                        .map(|param| param.substitute(&bindings, Some(&Type::any())))
+                        .collect::<Vec<_>>();
+                    Element::check_args(
+                        &params,
+                        args,
+                        arg_types,
+                        kwargs,
+                        kwarg_types,
+                        position,
+                        raw,
+                    )?;
+                } else if let Some(kwarg) = kwargs.keys().next() {
+                    return Err(
+                        ZyxtError::error_2_5_1(kwarg.to_owned()).with_pos_and_raw(position, raw)
+                    );
+                } else if !args.is_empty() {
+                    return Err(
+                        ZyxtError::error_2_5_0(0, args.len()).with_pos_and_raw(position, raw)
+                    );
+                }
+                Ok(called_type.generic_instance_type(&bindings))
+            }
+            Type::Instance {
+                implementation: Some(_),
+                ..
+            } => match called_type.get_attrs().get("_call") {
+                Some(Element::Procedure {
+                    args: params,
+                    return_type,
+                    ..
+                }) => {
+                    let bindings = called_type.generic_bindings();
+                    let params = params
+                        .iter()
+                        .skip(params.first().is_some_and(|param| param.name == "self") as usize)
+                        .map(|param| Param::from(param).substitute(&bindings, Some(&Type::any())))
+                        .collect::<Vec<_>>();
+                    Element::check_args(
+                        &params,
+                        args,
+                        arg_types,
+                        kwargs,
+                        kwarg_types,
+                        position,
+                        raw,
+                    )?;
+                    let mut return_type = return_type.substitute(&bindings, Some(&Type::any()));
+                    Element::resolve_type(&mut return_type, typelist);
+                    Ok(return_type)
+                }
+                Some(_) => Ok(Type::any()),
+                None => Ok(Type::null()),
+            },
+            Type::Nullable(_) => {
+                Err(ZyxtError::error_4_14_0(called_type).with_pos_and_raw(position, raw))
+            }
+            Type::Errorable(_) => {
+                Err(ZyxtError::error_4_15_0(called_type).with_pos_and_raw(position, raw))
+            }
+            called_type if called_type == Type::any() => Ok(Type::any()),
+            _ => Ok(Type::null()),
+        }?;
+        Ok(if is_null_safe {
+            return_type.nullable()
+        } else {
+            return_type
+        })
+    }
+    /// The type of the parent of an attribute, without null if the attribute is accessed with `?.`,
+    /// and whether the attribute is then null when the parent is
+    fn parent_type<O: Print, L: Print>(
+        parent: &mut Element,
+        typelist: &mut InterpreterData<Type, O>,
+        logger: &mut Logger<L>,
+    ) -> Result<(Type, bool), ZyxtError> {
+        if let Element::NullSafe { operand, .. } = parent {
+            let operand_type = operand.eval_type(typelist, logger)?;
+            let is_nullable = matches!(operand_type, Type::Nullable(_));
+            Ok((operand_type.non_null(), is_nullable))
+        } else {
+            Ok((parent.eval_type(typelist, logger)?, false))
+        }
+    }
+    /// The variables compared to null in a condition that cannot be null when the condition is `outcome`,
+    /// with their types without null
+    fn non_null_vars<O: Print>(
+        condition: &Element,
+        outcome: bool,
+        typelist: &mut InterpreterData<Type, O>,
+    ) -> Vec<(String, Type)> {
+        let (type_, operand1, operand2) = if let Element::BinaryOpr {
+            type_,
+            operand1,
+            operand2,
+            ..
+        } = condition
+        {
+            (type_, operand1, operand2)
+        } else {
+            return vec![];
+        };
+        match type_ {
+            // both sides of `&&` are true if it is, and both sides of `||` false if it is
+            OprType::And if outcome => {
+                let mut vars = Element::non_null_vars(operand1, outcome, typelist);
+                vars.extend(Element::non_null_vars(operand2, outcome, typelist));
+                return vars;
+            }
+            OprType::Or if !outcome => {
+                let mut vars = Element::non_null_vars(operand1, outcome, typelist);
+                vars.extend(Element::non_null_vars(operand2, outcome, typelist));
+                return vars;
+            }
+            OprType::Noteq | OprType::Isnteq if outcome => (),
+            OprType::Eq | OprType::Iseq if !outcome => (),
+            _ => return vec![],
+        }
+        let is_null =
+            |e: &Element| matches!(e, Element::Literal { type_, .. } if *type_ == Type::null());
+        let variable = match (&**operand1, &**operand2) {
+            (variable, null) | (null, variable) if is_null(null) => variable,
+            _ => return vec![],
+        };
+        if let Element::Variable {
+            name,
+            parent,
+            position,
+            raw,
+        } = variable
+        {
+            if **parent == Element::NullElement {
+                if let Ok(type_ @ Type::Nullable(_)) = typelist.get_val(name, position, raw) {
+                    return vec![(name.to_owned(), type_.non_null())];
+                }
+            }
+        }
+        vec![]
+    }
+    /// The type of the attribute `name` of a value of type `parent_type`.
+    /// Instances may have attributes that their class does not declare, given to them by `_init`
+    fn attr_type<O: Print, L: Print>(
+        parent: &Element,
+        parent_type: &Type,
+        name: &str,
+        typelist: &mut InterpreterData<Type, O>,
+        logger: &mut Logger<L>,
+    ) -> Result<Type, ZyxtError> {
+        if *parent_type == Type::any() {
+            return Ok(Type::any());
+        } else if let Type::Nullable(_) = parent_type {
+            return Err(ZyxtError::error_4_14_0(parent_type.to_owned()).with_element(parent));
+        } else if let Type::Errorable(_) = parent_type {
+            return Err(ZyxtError::error_4_15_0(parent_type.to_owned()).with_element(parent));
+        } else if *parent_type == Type::from_name("err")
+            && ["code", "message", "position"].contains(&name)
+        {
+            // errors caught as values have the code, message and position of the error
+            return Ok(Type::from_name("str"));
+        }
+        if let Some(fields) = parent_type.fields() {
+            if let Some(field) = fields.iter().find(|field| field.name == name) {
+                return Ok(field
+                    .type_
+                    .substitute(&parent_type.generic_bindings(), Some(&Type::any())));
+            }
+        }
+        if let Type::Enum { variants, .. } = parent_type {
+            return match variants.get(name) {
+                // struct variants are constructed from their fields
+                Some(Element::Class {
+                    is_struct: true,
+                    args,
+                    ..
+                }) => Ok(Type::proc(
+                    true,
+                    args.as_deref().unwrap_or_default(),
+                    parent_type.instance_type(),
+                )),
+                Some(_) => Ok(parent_type.instance_type()),
+                None => Err(
+                    ZyxtError::error_3_1_2(parent_type.to_owned(), name.to_string())
+                        .with_element(parent),
+                ),
+            };
+        }
+        let is_instance = matches!(
+            parent_type,
+            Type::Instance {
+                implementation: Some(_),
+                ..
+            }
+        );
+        let attr = if is_instance || matches!(parent_type, Type::Definition { .. }) {
+            Element::check_visibility(parent, parent_type, name, typelist)?;
+            parent_type.get_attrs().get(name).cloned()
+        } else {
+            None
+        };
+        match attr {
+            // methods are checked along with their class
+            Some(Element::Procedure {
+                is_fn,
+                args,
+                return_type,
+                ..
+            }) => {
+                let mut return_type = return_type;
+                Element::resolve_type(&mut return_type, typelist);
+                // the generic parameters of the class are given the types in the type of the instance
+                Ok(Type::proc(is_fn, &args, return_type)
+                    .substitute(&parent_type.generic_bindings(), None))
+            }
+            Some(mut attr) => attr.eval_type(typelist, logger),
+            // instances of classes also have the attributes `_init` gives them, while structs have only their fields
+            None if is_instance && parent_type.fields().is_none() => {
+                match parent_type.init_attr_type(name) {
+                    Some(attr_type) => {
+                        Ok(attr_type
+                            .substitute(&parent_type.generic_bindings(), Some(&Type::any())))
+                    }
+                    None => Err(ZyxtError::error_3_1_0(
+                        parent.to_owned(),
+                        parent_type.to_owned(),
+                        name.to_string(),
+                    )
+                    .with_element(parent)),
+                }
+            }
+            None => Err(ZyxtError::error_3_1_0(
+                parent.to_owned(),
+                parent_type.to_owned(),
+                name.to_string(),
+            )
+            .with_element(parent)),
+        }
+    }
+    /// Checks that a `priv` attribute is only used in the body of its class,
+    /// and a `prot` one only in the bodies of its class and the classes inheriting from it
+    fn check_visibility<O: Print>(
+        parent: &Element,
+        parent_type: &Type,
+        name: &str,
+        typelist: &InterpreterData<Type, O>,
+    ) -> Result<(), ZyxtError> {
+        let owner = if let Some(owner) = parent_type.attr_owner(name) {
+            owner
+        } else {
+            return Ok(());
+        };
+        let flags = parent_type.attr_flags(name);
+        let is_owner =
+            |class: &Type| matches!(class, Type::Definition { name, .. } if *name == owner);
+        if flags.contains(&Flag::Priv) && !typelist.classes.iter().any(is_owner) {
+            Err(ZyxtError::error_3_5_0(owner, name.to_string()).with_element(parent))
+        } else if flags.contains(&Flag::Prot)
+            && !typelist
+                .classes
+                .iter()
+                .any(|class| class.mro().unwrap_or_default().iter().any(is_owner))
+        {
+            Err(ZyxtError::error_3_5_1(owner, name.to_string()).with_element(parent))
+        } else {
+            Ok(())
+        }
+    }
+    /// Resolves the names of classes in a type annotation to the type of their instances
+    fn resolve_type<O: Print>(type_: &mut Type, typelist: &InterpreterData<Type, O>) {
+        if let Type::Nullable(type_) | Type::Errorable(type_) = type_ {
+            return Element::resolve_type(type_, typelist);
+        }
+        if let Type::Instance {
+            name,
+            type_args,
+            implementation: None,
+            ..
+        } = type_
+        {
+            for type_arg in type_args.iter_mut() {
+                Element::resolve_type(type_arg, typelist);
+            }
+            let definition = typelist
+                .heap
+                .iter()
+                .rev()
+                .find_map(|frame| frame.borrow().get(name).cloned());
+            if let Some(definition @ (Type::Definition { .. } | Type::Enum { .. })) = definition {
+                let type_args = std::mem::take(type_args);
+                *type_ = definition.instance_type();
+                if let Type::Instance {
+                    type_args: resolved_type_args,
+                    ..
+                } = type_
+                {
+                    *resolved_type_args = type_args;
+                }
+            }
+        }
+    }
+    /// Turns references to a class in the signatures of its methods back into its name,
+    /// as its type is not complete until they are checked. They are resolved again where the methods are used
+    fn unresolve_type(type_: &mut Type, class_name: &Option<String>) {
+        if let Type::Instance {
+            name,
+            inst_attrs,
+            implementation,
+            ..
+        } = type_
+        {
+            if Some(&*name) == class_name.as_ref() {
+                *inst_attrs = HashMap::new();
+                *implementation = None;
+            }
+        }
+    }
+    /// Checks that `content` can be assigned to `variable`, as by `variable = content`
+    fn assignment_type<O: Print, L: Print>(
+        variable: &Element,
+        content: &Element,
+        typelist: &mut InterpreterData<Type, O>,
+        logger: &mut Logger<L>,
+        position: &Position,
+        raw: &str,
+    ) -> Result<Type, ZyxtError> {
+        Element::Set {
+            position: position.to_owned(),
+            raw: raw.to_string(),
+            variable: Box::new(variable.to_owned()),
+            content: Box::new(content.to_owned()),
+        }
+        .eval_type(typelist, logger)
+    }
+    /// The flags the attributes declared in the body of a class were declared with
+    pub fn attr_flags(content: &[Element]) -> HashMap<String, Vec<Flag>> {
+        content
+            .iter()
+            .filter_map(|expr| match expr {
+                Element::Declare {
+                    variable, flags, ..
+                } => Some((variable.get_name(), flags.to_owned())),
+                _ => None,
+            })
+            .collect()
+    }
+    /// The variable that an assignment to an item or attribute of it changes
+    pub fn root_variable(&self) -> &Element {
+        match self {
+            Element::Index { parent, .. } => parent.root_variable(),
+            Element::Variable { parent, .. } if **parent != Element::NullElement => {
+                parent.root_variable()
+            }
+            Element::NullSafe { operand, .. } => operand.root_variable(),
+            _ => self,
+        }
+    }
+    /// Checks that the variable an assignment to an item or attribute changes can be changed.
+    /// Through a pointer, it is the variable the pointer points to
+    fn check_assignable<O: Print, L: Print>(
+        root: &Element,
+        typelist: &mut InterpreterData<Type, O>,
+        logger: &mut Logger<L>,
+    ) -> Result<(), ZyxtError> {
+        if let Element::UnaryOpr {
+            type_: OprType::Deref,
+            operand,
+            ..
+        } = root
+        {
+            let ref_type = operand.to_owned().eval_type(typelist, logger)?;
+            return match ref_type.referenced() {
+                Some((_, true)) => Ok(()),
+                None if ref_type == Type::any() => Ok(()),
+                Some((_, false)) => Err(ZyxtError::error_4_16(
+                    operand.get_raw().trim().to_string(),
+                    ref_type,
+                )
+                .with_element(operand)),
+                None => Err(ZyxtError::error_4_0_1(
+                    OprType::Deref.to_string(),
+                    ref_type.to_string(),
+                )
+                .with_element(operand)),
+            };
+        }
+        if !root.is_pattern() {
+            return Err(ZyxtError::error_2_2(root.to_owned()).with_element(root));
+        }
+        if typelist.is_read_only(&root.get_name()) {
+            return Err(ZyxtError::error_3_3(root.get_name()).with_element(root));
+        }
+        if typelist.is_const(&root.get_name()) {
+            return Err(ZyxtError::error_3_4(root.get_name()).with_element(root));
+        }
+        Ok(())
+    }
+    pub fn is_pattern(&self) -> bool {
+        matches!(self, Element::Variable { .. })
+    }
+    pub fn eval_type<O: Print, L: Print>(
+        &mut self,
+        typelist: &mut InterpreterData<Type, O>,
+        logger: &mut Logger<L>,
+    ) -> Result<Type, ZyxtError> {
+        match self {
+            Element::Literal { type_, .. } => Ok(type_.to_owned()),
+            Element::Variable {
+                name,
+                position,
+                raw,
+                parent,
+            } => {
+                if **parent == Element::NullElement {
+                    typelist.get_val(name, position, raw)
+                } else {
+                    let (parent_type, is_null_safe) =
+                        Element::parent_type(parent, typelist, logger)?;
+                    let attr_type =
+                        Element::attr_type(parent, &parent_type, name, typelist, logger)?;
+                    // an attribute accessed with `?.` is null if its parent is
+                    Ok(if is_null_safe {
+                        attr_type.nullable()
+                    } else {
+                        attr_type
+                    })
+                }
+            }
+            Element::NullSafe { operand, .. } => {
+                Ok(operand.eval_type(typelist, logger)?.non_null())
+            }
+            Element::Block { content, .. } => Ok(Element::labelled_block_type(
+                content,
+                typelist,
+                true,
+                LabelKind::Block,
+                &None,
+                logger,
+            )?
+            .0),
+            Element::Call {
+                called,
+                args,
+                kwargs,
+                position,
+                raw,
+            } => Element::call_return_type(called, args, kwargs, typelist, logger, position, raw),
+            Element::Declare {
+                position,
+                variable,
+                content,
+                flags,
+                type_,
+                raw,
+            } => {
+                if !variable.is_pattern() {
+                    return Err(
+                        ZyxtError::error_2_2(*variable.to_owned()).with_element(&**variable)
+                    );
+                }
+                // classes, structs and enums are named after the variable they are declared as
+                if let Element::Class {
+                    name: name @ None, ..
+                }
+                | Element::Enum {
+                    name: name @ None, ..
+                } = &mut **content
+                {
+                    *name = Some(variable.get_name());
+                }
+                let var_name = variable.get_name();
+                if typelist
+                    .consts
+                    .last()
+                    .is_some_and(|consts| consts.contains(&var_name))
+                {
+                    return Err(ZyxtError::error_3_4(var_name).with_pos_and_raw(position, raw));
+                }
+                if typelist.is_referenced(&var_name) {
+                    return Err(ZyxtError::error_3_7(var_name).with_pos_and_raw(position, raw));
+                }
+                if let [first, second, ..] = &*flags
+                    .iter()
+                    .filter(|flag| [Flag::Pub, Flag::Priv, Flag::Prot].contains(flag))
+                    .map(|flag| format!("{:?}", flag).to_lowercase())
+                    .collect::<Vec<_>>()
+                {
+                    return Err(ZyxtError::error_3_5_2(first.to_owned(), second.to_owned())
+                        .with_pos_and_raw(position, raw));
+                }
+                let is_const = flags.contains(&Flag::Const);
+                // procedures note the procedures their bodies use before they are declared
+                let is_proc = matches!(
+                    **content,
+                    Element::Procedure { .. } | Element::Overload { .. }
+                );
+                if is_proc {
+                    typelist.pending_forward_refs.push(Default::default());
+                }
+                let content_type = content.eval_type(typelist, logger);
+                let forward_refs = if is_proc {
+                    typelist.pending_forward_refs.pop().unwrap_or_default()
+                } else {
+                    Default::default()
+                };
+                let content_type = content_type?;
+                if *type_ == Type::null() {
+                    typelist.declare_val(&variable.get_name(), &content_type);
+                    *self = Element::Declare {
+                        type_: content_type.to_owned(),
+                        content: content.to_owned(),
+                        variable: variable.to_owned(),
+                        position: position.to_owned(),
+                        raw: raw.to_owned(),
+                        flags: flags.to_owned(),
                     };
                 } else {
+                    Element::resolve_type(type_, typelist);
                     typelist.declare_val(&variable.get_name(), type_);
-                    if content_type != *type_ {
+                    if !type_.accepts(&content_type) {
+                        // procedures cannot be cast to another type
+                        if content_type == Type::null()
+                            || matches!(type_, Type::Proc { .. })
+                            || matches!(content_type, Type::Proc { .. } | Type::Overload(_))
+                        {
+                            return Err(ZyxtError::error_4_3(
+                                var_name,
+                                type_.to_owned(),
+                                content_type,
+                            )
+                            .with_pos_and_raw(position, raw));
+                        } else if let Type::Nullable(_) = content_type {
+                            return Err(ZyxtError::error_4_14_0(content_type).with_element(content));
+                        } else if let Type::Errorable(_) = content_type {
+                            return Err(ZyxtError::error_4_15_0(content_type).with_element(content));
+                        }
+                    }
+                    if !type_.accepts(&content_type) {
                         let new_content = Element::BinaryOpr {
                             position: position.to_owned(),
                             raw: raw.to_owned(),
@@ -412,11 +1820,260 @@ impl Element {
                         };
                     }
                 };
+                if !forward_refs.is_empty() {
+                    typelist
+                        .forward_refs
+                        .last_mut()
+                        .unwrap()
+                        .insert(var_name.to_owned(), forward_refs);
+                }
+                if is_const {
+                    typelist.mark_const(&var_name);
+                }
                 Ok(content_type)
             }
+            Element::Array {
+                is_vec, content, ..
+            } => Ok(Type::sequence(
+                Element::common_type(content.iter_mut(), typelist, logger)?,
+                *is_vec,
+            )),
+            Element::Tuple { content, .. } => Ok(Type::generic(
+                "tuple",
+                content
+                    .iter_mut()
+                    .map(|item| item.eval_type(typelist, logger))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+            Element::SetLiteral {
+                is_frozen, content, ..
+            } => Ok(Type::generic(
+                if *is_frozen { "fset" } else { "set" },
+                vec![Element::common_type(content.iter_mut(), typelist, logger)?],
+            )),
+            Element::Dict { content, .. } => {
+                let key_type =
+                    Element::common_type(content.iter_mut().map(|(k, _)| k), typelist, logger)?;
+                let value_type =
+                    Element::common_type(content.iter_mut().map(|(_, v)| v), typelist, logger)?;
+                Ok(Type::generic("dict", vec![key_type, value_type]))
+            }
+            Element::Index { parent, index, .. } => {
+                let parent_type = parent.eval_type(typelist, logger)?;
+                match &parent_type {
+                    Type::Instance {
+                        name, type_args, ..
+                    } if name == "dict" => {
+                        let key_type = index.eval_type(typelist, logger)?;
+                        let dict_key_type = type_args.first().cloned().unwrap_or_else(Type::any);
+                        if key_type != dict_key_type
+                            && key_type != Type::any()
+                            && dict_key_type != Type::any()
+                        {
+                            return Err(
+                                ZyxtError::error_4_8_4(dict_key_type, key_type).with_element(index)
+                            );
+                        }
+                        Ok(type_args.get(1).cloned().unwrap_or_else(Type::any))
+                    }
+                    Type::Instance {
+                        name, type_args, ..
+                    } if name == "tuple" => {
+                        Element::index_type(index, typelist, logger)?;
+                        // with a literal index, the type of that exact item is known
+                        if let Element::Literal { type_, content, .. } = &**index {
+                            return content
+                                .parse::<usize>()
+                                .ok()
+                                .and_then(|i| type_args.get(i).cloned())
+                                .ok_or_else(|| {
+                                    ZyxtError::error_4_8_2(
+                                        Value::from_type_content(
+                                            type_.to_owned(),
+                                            content.to_owned(),
+                                        ),
+                                        type_args.len(),
+                                    )
+                                    .with_element(index)
+                                });
+                        }
+                        Ok(parent_type.item_type().unwrap())
+                    }
+                    Type::Instance { name, .. } if name == "set" || name == "fset" => {
+                        Err(ZyxtError::error_4_8_0(parent_type).with_element(parent))
+                    }
+                    _ => {
+                        Element::index_type(index, typelist, logger)?;
+                        parent_type
+                            .item_type()
+                            .ok_or_else(|| ZyxtError::error_4_8_0(parent_type).with_element(parent))
+                    }
+                }
+            }
+            Element::Slice {
+                parent, start, end, ..
+            } => {
+                let parent_type = parent.eval_type(typelist, logger)?;
+                for bound in [start, end] {
+                    if **bound != Element::NullElement {
+                        Element::index_type(bound, typelist, logger)?;
+                    }
+                }
+                if !matches!(&parent_type, Type::Instance { name, .. }
+                    if ["str", "array", "vec", "_any"].contains(&&**name))
+                {
+                    return Err(ZyxtError::error_4_8_0(parent_type).with_element(parent));
+                }
+                Ok(parent_type)
+            }
             Element::If { conditions, .. } => {
-                Ok(Element::block_type(&mut conditions[0].if_true, typelist, true)?.0)
+                // a variable checked against null is not null in the branches where it cannot be
+                let mut narrowed: Vec<(String, Type)> = vec![];
+                let mut if_type = None;
+                for cond in conditions.iter_mut() {
+                    typelist.add_frame(None);
+                    for (name, type_) in &narrowed {
+                        typelist.narrow(name, type_);
+                    }
+                    if cond.condition != Element::NullElement {
+                        Element::check_condition(&mut cond.condition, typelist, logger)?;
+                        for (name, type_) in Element::non_null_vars(&cond.condition, true, typelist)
+                        {
+                            typelist.narrow(&name, &type_);
+                        }
+                        narrowed.extend(Element::non_null_vars(&cond.condition, false, typelist));
+                    }
+                    let branch_type =
+                        Element::block_type(&mut cond.if_true, typelist, true, logger)?;
+                    typelist.pop_frame();
+                    if_type.get_or_insert(branch_type);
+                }
+                Ok(if_type.unwrap_or_else(Type::null))
             } // TODO consider all returns
+            Element::Match { subject, arms, .. } => {
+                let subject_type = subject.eval_type(typelist, logger)?;
+                let mut result_type: Option<Type> = None;
+                let mut caught_all = false;
+                let mut seen_patterns = vec![];
+                for arm in arms.iter_mut() {
+                    typelist.add_frame(None);
+                    let (can_match, catches_all) =
+                        arm.pattern.eval_type(&subject_type, typelist, logger)?;
+                    let mut repeated = false;
+                    if let Pattern::Value(value) | Pattern::Type(value) = &arm.pattern {
+                        let pattern_raw = value.get_raw().trim().to_string();
+                        repeated = seen_patterns.contains(&pattern_raw);
+                        seen_patterns.push(pattern_raw);
+                    }
+                    if caught_all || repeated || !can_match {
+                        logger.warn(format!(
+                            "{}: unreachable match arm `{}`",
+                            arm.position,
+                            arm.raw.trim()
+                        ));
+                    }
+                    caught_all |= catches_all;
+
+                    let arm_type = Element::block_type(&mut arm.content, typelist, false, logger)?;
+                    typelist.pop_frame();
+                    if let Some(result_type) = &result_type {
+                        if arm_type != *result_type {
+                            return Err(ZyxtError::error_4_6(result_type.to_owned(), arm_type)
+                                .with_pos_and_raw(&arm.position, &arm.raw));
+                        }
+                    } else {
+                        result_type = Some(arm_type);
+                    }
+                }
+                let result_type = result_type.unwrap_or_else(Type::null);
+                // the match evaluates to null if no arm matches
+                Ok(if caught_all {
+                    result_type
+                } else {
+                    result_type.nullable()
+                })
+            }
+            Element::While {
+                condition,
+                content,
+                label,
+                ..
+            } => {
+                Element::check_condition(condition, typelist, logger)?;
+                Element::labelled_block_type(
+                    content,
+                    typelist,
+                    true,
+                    LabelKind::Loop,
+                    label,
+                    logger,
+                )?;
+                Ok(Type::null())
+            }
+            Element::For {
+                variable,
+                type_,
+                iterable,
+                content,
+                label,
+                ..
+            } => {
+                let iterable_type = iterable.eval_type(typelist, logger)?;
+                let item_type = if let Some(item_type) = iterable_type.iter_type() {
+                    item_type
+                } else {
+                    return Err(ZyxtError::error_4_5_0(iterable_type).with_element(iterable));
+                };
+                if *type_ == Type::null() {
+                    *type_ = item_type;
+                } else {
+                    Element::resolve_type(type_, typelist);
+                    // the items are converted to the type of the variable, which only numbers can be
+                    if !type_.accepts(&item_type) && (!type_.is_num() || !item_type.is_num()) {
+                        return Err(ZyxtError::error_4_3(
+                            variable.get_name(),
+                            type_.to_owned(),
+                            item_type,
+                        )
+                        .with_element(variable));
+                    }
+                }
+                typelist.add_frame(None);
+                typelist.declare_val(&variable.get_name(), type_);
+                Element::labelled_block_type(
+                    content,
+                    typelist,
+                    false,
+                    LabelKind::Loop,
+                    label,
+                    logger,
+                )?;
+                typelist.pop_frame();
+                Ok(Type::null())
+            }
+            Element::CStyleFor {
+                init,
+                condition,
+                step,
+                content,
+                label,
+                ..
+            } => {
+                typelist.add_frame(None);
+                init.eval_type(typelist, logger)?;
+                Element::check_condition(condition, typelist, logger)?;
+                step.eval_type(typelist, logger)?;
+                Element::labelled_block_type(
+                    content,
+                    typelist,
+                    true,
+                    LabelKind::Loop,
+                    label,
+                    logger,
+                )?;
+                typelist.pop_frame();
+                Ok(Type::null())
+            }
             Element::BinaryOpr {
                 type_,
                 operand1,
@@ -425,12 +2082,42 @@ impl Element {
                 raw,
                 ..
             } => {
-                let type1 = operand1.eval_type(typelist)?;
-                let type2 = operand2.eval_type(typelist)?;
+                let type1 = operand1.eval_type(typelist, logger)?;
+                // the right of `&&` is only evaluated if the left is true, and the right of `||` if it is false
+                let type2 = if [OprType::And, OprType::Or].contains(type_) {
+                    let narrowed =
+                        Element::non_null_vars(operand1, type_ == &OprType::And, typelist);
+                    typelist.add_frame(None);
+                    for (name, type_) in &narrowed {
+                        typelist.narrow(name, type_);
+                    }
+                    let type2 = operand2.eval_type(typelist, logger);
+                    typelist.pop_frame();
+                    type2?
+                } else {
+                    operand2.eval_type(typelist, logger)?
+                };
+                if type_ == &OprType::Swap {
+                    // each side is assigned the other
+                    for (variable, content) in [(&*operand1, &*operand2), (&*operand2, &*operand1)]
+                    {
+                        Element::assignment_type(
+                            variable, content, typelist, logger, position, raw,
+                        )?;
+                    }
+                    return Ok(Type::null());
+                }
                 if type_ == &OprType::TypeCast && type2 == Type::from_name("type") {
                     return Ok(Type::from_name(&*operand2.get_name()));
+                } else if type_ == &OprType::TypeCast
+                    && matches!(type2, Type::Definition { .. } | Type::Enum { .. })
+                {
+                    return Ok(type2.instance_type());
                 }
-                Element::bin_op_return_type(type_, type1, type2, position, raw)
+                let mut return_type =
+                    Element::bin_op_return_type(type_, type1, type2, position, raw)?;
+                Element::resolve_type(&mut return_type, typelist);
+                Ok(return_type)
             }
             Element::UnaryOpr {
                 type_,
@@ -439,27 +2126,109 @@ impl Element {
                 raw,
                 ..
             } => {
-                let opnd_type = operand.eval_type(typelist)?;
-                Element::un_op_return_type(type_, opnd_type, position, raw)
+                if let OprType::Ref | OprType::Pointer = type_ {
+                    if !matches!(&**operand, Element::Variable { parent, .. } if **parent == Element::NullElement)
+                    {
+                        return Err(ZyxtError::error_2_7(*operand.to_owned()).with_element(operand));
+                    }
+                    // a pointer can change the variable, so it cannot point to one that cannot be changed
+                    if type_ == &OprType::Pointer {
+                        if typelist.is_read_only(&operand.get_name()) {
+                            return Err(ZyxtError::error_3_3(operand.get_name())
+                                .with_pos_and_raw(position, raw));
+                        }
+                        if typelist.is_const(&operand.get_name()) {
+                            return Err(ZyxtError::error_3_4(operand.get_name())
+                                .with_pos_and_raw(position, raw));
+                        }
+                    }
+                    let opnd_type = operand.eval_type(typelist, logger)?;
+                    typelist.mark_referenced(&operand.get_name());
+                    return Ok(Type::reference(opnd_type, type_ == &OprType::Pointer));
+                }
+                let opnd_type = operand.eval_type(typelist, logger)?;
+                if let OprType::Increment | OprType::Decrement = type_ {
+                    // the operand is assigned its new value
+                    Element::assignment_type(operand, operand, typelist, logger, position, raw)?;
+                }
+                if type_ == &OprType::Deref {
+                    return if let Some((referenced, _)) = opnd_type.referenced() {
+                        Ok(referenced)
+                    } else if opnd_type == Type::any() {
+                        Ok(Type::any())
+                    } else {
+                        Err(
+                            ZyxtError::error_4_0_1(type_.to_string(), opnd_type.to_string())
+                                .with_pos_and_raw(position, raw),
+                        )
+                    };
+                }
+                let mut return_type = Element::un_op_return_type(type_, opnd_type, position, raw)?;
+                Element::resolve_type(&mut return_type, typelist);
+                Ok(return_type)
             }
             Element::Procedure {
                 is_fn,
                 return_type,
                 content,
                 args,
+                label,
                 position,
                 raw,
                 ..
             } => {
+                // variables captured by a function are read-only inside it
+                let read_only_depth = typelist.read_only_depth;
+                if *is_fn {
+                    typelist.read_only_depth = typelist.heap.len();
+                }
+                Element::resolve_type(return_type, typelist);
+                for arg in args.iter_mut() {
+                    Element::resolve_type(&mut arg.type_, typelist);
+                    if let Some(default) = &mut arg.default {
+                        let default_type = default.eval_type(typelist, logger)?;
+                        Element::coerce_arg(default, &default_type, &arg.name, &arg.type_)?;
+                    }
+                }
                 typelist.add_frame(None);
-                for arg in args {
-                    typelist.declare_val(&arg.name, &arg.type_);
+                let proc_depth =
+                    std::mem::replace(&mut typelist.proc_depth, typelist.heap.len() - 1);
+                for arg in args.iter() {
+                    typelist.declare_val(&arg.name, &arg.value_type());
                 }
-                let (res, block_return_type) = Element::block_type(content, typelist, false)?;
-                if return_type == &Type::null() || block_return_type.is_none() {
+                // function methods cannot change the instance they are called on, except `_init` building it
+                if *is_fn
+                    && typelist.init_attrs.is_none()
+                    && args.first().is_some_and(|arg| arg.name == "self")
+                {
+                    typelist.mark_const("self");
+                }
+                let res = Element::labelled_block_type(
+                    content,
+                    typelist,
+                    false,
+                    LabelKind::Proc,
+                    label,
+                    logger,
+                );
+                typelist.pop_frame();
+                typelist.read_only_depth = read_only_depth;
+                typelist.proc_depth = proc_depth;
+                let (res, block_return_type) = res?;
+                if return_type != &Type::null() && !return_type.accepts(&res) {
+                    if let Type::Nullable(_) = res {
+                        return Err(ZyxtError::error_4_14_0(res).with_pos_and_raw(position, raw));
+                    } else if let Type::Errorable(_) = res {
+                        return Err(ZyxtError::error_4_15_0(res).with_pos_and_raw(position, raw));
+                    }
+                }
+                // a declared nullable or errorable return type is kept when the body returns a T
+                if return_type == &Type::null()
+                    || (block_return_type.is_none() && !return_type.accepts(&res))
+                {
                     *return_type = res;
                 } else if let Some(block_return_type) = block_return_type {
-                    if *return_type == block_return_type {
+                    if !return_type.accepts(&block_return_type) {
                         return Err(ZyxtError::error_4_t(
                             return_type.to_owned(),
                             block_return_type,
@@ -467,26 +2236,30 @@ impl Element {
                         .with_pos_and_raw(position, raw));
                     }
                 }
-                Ok(Type::Instance {
-                    name: if *is_fn { "fn" } else { "proc" }.to_string(),
-                    type_args: vec![Type::null(), return_type.to_owned()],
-                    inst_attrs: Default::default(),
-                    implementation: None,
-                })
-            } // TODO angle bracket thingy when it is implemented
+                Ok(Type::proc(*is_fn, args, return_type.to_owned()))
+            }
+            Element::Overload { procs, .. } => {
+                for proc in procs.iter_mut() {
+                    proc.eval_type(typelist, logger)?;
+                }
+                Ok(Type::Overload(procs.to_owned()))
+            }
             Element::Preprocess { content, .. } => {
                 let mut pre_typelist = InterpreterData::default_type(typelist.out);
-                let pre_instructions = gen_instructions(content.to_owned(), &mut pre_typelist)?;
+                let pre_instructions =
+                    gen_instructions(content.to_owned(), &mut pre_typelist, logger)?;
+                let max_call_depth = typelist.max_call_depth;
                 let mut i_data = InterpreterData::default_variable(typelist.out);
-                let pre_value = interpret_block(&pre_instructions, &mut i_data, true, false)?;
+                i_data.max_call_depth = max_call_depth;
+                let pre_value = match interpret_block(&pre_instructions, &mut i_data, false) {
+                    Ok(v) => v,
+                    Err(ControlFlow::Return { label: None, value }) => *value,
+                    Err(flow) => return Err(flow.into_error()),
+                };
                 *self = pre_value.as_element();
-                self.eval_type(typelist)
-            }
-            Element::Defer { content, .. } =>
-            // TODO check block return against call stack
-            {
-                Ok(Element::block_type(content, typelist, false)?.0)
+                self.eval_type(typelist, logger)
             }
+            Element::Defer { content, .. } => Element::block_type(content, typelist, false, logger),
             Element::Set {
                 position,
                 variable,
@@ -494,33 +2267,264 @@ impl Element {
                 raw,
                 ..
             } => {
+                if let Element::UnaryOpr {
+                    type_: OprType::Deref,
+                    operand,
+                    ..
+                } = &mut **variable
+                {
+                    let ref_type = operand.eval_type(typelist, logger)?;
+                    let content_type = content.eval_type(typelist, logger)?;
+                    return match ref_type.referenced() {
+                        Some((referenced, true)) if !referenced.accepts(&content_type) => {
+                            Err(ZyxtError::error_4_3(
+                                variable.get_raw().trim().to_string(),
+                                referenced,
+                                content_type,
+                            )
+                            .with_pos_and_raw(position, raw))
+                        }
+                        Some((_, true)) => Ok(content_type),
+                        Some((_, false)) => Err(ZyxtError::error_4_16(
+                            operand.get_raw().trim().to_string(),
+                            ref_type,
+                        )
+                        .with_element(operand)),
+                        None if ref_type == Type::any() => Ok(content_type),
+                        None => Err(ZyxtError::error_4_0_1(
+                            OprType::Deref.to_string(),
+                            ref_type.to_string(),
+                        )
+                        .with_element(operand)),
+                    };
+                }
+                if let Element::Index { parent, .. } = &mut **variable {
+                    Element::check_assignable(parent.root_variable(), typelist, logger)?;
+                    let parent_type = parent.eval_type(typelist, logger)?;
+                    if !matches!(&parent_type, Type::Instance { name, .. } if ["vec", "dict", "_any"].contains(&&**name))
+                    {
+                        return Err(ZyxtError::error_4_8_3(parent_type).with_element(parent));
+                    }
+                    let item_type = variable.eval_type(typelist, logger)?;
+                    let content_type = content.eval_type(typelist, logger)?;
+                    return if !item_type.accepts(&content_type) {
+                        Err(ZyxtError::error_4_3(
+                            variable.get_raw().trim().to_string(),
+                            item_type,
+                            content_type,
+                        )
+                        .with_pos_and_raw(position, raw))
+                    } else {
+                        Ok(item_type)
+                    };
+                }
+                if let Element::Variable { parent, name, .. } = &mut **variable {
+                    if **parent != Element::NullElement
+                        && parent.is_pattern()
+                        && parent.get_name() == "self"
+                        && typelist.init_attrs.is_some()
+                    {
+                        // the attributes `_init` gives `self` have the type they are first assigned
+                        let content_type = content.eval_type(typelist, logger)?;
+                        let init_attrs = typelist.init_attrs.as_mut().unwrap();
+                        return match init_attrs.get(name) {
+                            Some(attr_type) if !attr_type.accepts(&content_type) => {
+                                Err(ZyxtError::error_4_3(
+                                    variable.get_raw().trim().to_string(),
+                                    attr_type.to_owned(),
+                                    content_type,
+                                )
+                                .with_pos_and_raw(position, raw))
+                            }
+                            Some(_) => Ok(content_type),
+                            None => {
+                                init_attrs.insert(name.to_owned(), content_type.to_owned());
+                                Ok(content_type)
+                            }
+                        };
+                    }
+                    if **parent != Element::NullElement {
+                        Element::check_assignable(parent.root_variable(), typelist, logger)?;
+                        let parent_type = parent.eval_type(typelist, logger)?;
+                        let attr_type =
+                            Element::attr_type(parent, &parent_type, name, typelist, logger)?;
+                        if parent_type.attr_flags(name).contains(&Flag::Const) {
+                            return Err(ZyxtError::error_3_4(
+                                variable.get_raw().trim().to_string(),
+                            )
+                            .with_pos_and_raw(position, raw));
+                        }
+                        let content_type = content.eval_type(typelist, logger)?;
+                        return if !attr_type.accepts(&content_type) {
+                            Err(ZyxtError::error_4_3(
+                                variable.get_raw().trim().to_string(),
+                                attr_type,
+                                content_type,
+                            )
+                            .with_pos_and_raw(position, raw))
+                        } else {
+                            Ok(content_type)
+                        };
+                    }
+                }
                 if !variable.is_pattern() {
                     return Err(
                         ZyxtError::error_2_2(*variable.to_owned()).with_element(&**variable)
                     );
                 }
-                let content_type = content.eval_type(typelist)?;
-                let var_type = typelist.get_val(&variable.get_name(), position, raw)?;
-                if content_type != var_type {
+                if variable.get_name() == "self" {
+                    return Err(ZyxtError::error_3_6().with_pos_and_raw(position, raw));
+                }
+                if typelist.is_read_only(&variable.get_name()) {
+                    return Err(
+                        ZyxtError::error_3_3(variable.get_name()).with_pos_and_raw(position, raw)
+                    );
+                }
+                if typelist.is_const(&variable.get_name()) {
+                    return Err(
+                        ZyxtError::error_3_4(variable.get_name()).with_pos_and_raw(position, raw)
+                    );
+                }
+                let content_type = content.eval_type(typelist, logger)?;
+                let var_type = typelist.get_declared_val(&variable.get_name(), position, raw)?;
+                if !var_type.accepts(&content_type) {
                     Err(
                         ZyxtError::error_4_3(variable.get_name(), var_type, content_type)
                             .with_pos_and_raw(position, raw),
                     )
                 } else {
+                    // a variable narrowed to not be null may be null again
+                    if content_type == Type::any()
+                        || content_type == Type::null()
+                        || matches!(content_type, Type::Nullable(_))
+                    {
+                        typelist.unnarrow(&variable.get_name());
+                    }
                     Ok(var_type)
                 }
             }
             Element::Class {
+                name,
                 content,
+                class_attrs,
                 inst_attrs,
+                generics,
                 args,
                 is_struct,
-                ..
+                is_mixin: is_mixin_class,
+                position,
+                raw,
             } => {
+                for arg in args.iter_mut().flatten() {
+                    Element::resolve_type(&mut arg.type_, typelist);
+                    if let Some(default) = &mut arg.default {
+                        let default_type = default.eval_type(typelist, logger)?;
+                        Element::coerce_arg(default, &default_type, &arg.name, &arg.type_)?;
+                    }
+                }
+                // the generic parameters of the class can be used in the signatures of its methods
+                for expr in content.iter_mut() {
+                    if let Element::Declare { content, .. } = expr {
+                        if let Element::Procedure {
+                            args, return_type, ..
+                        } = &mut **content
+                        {
+                            for arg in args.iter_mut() {
+                                arg.type_ = arg.type_.to_owned().with_generics(generics);
+                            }
+                            *return_type = return_type.to_owned().with_generics(generics);
+                        }
+                    }
+                }
                 typelist.add_frame(None);
-                let class_attrs = HashMap::new();
+                let mut parents = vec![];
+                for expr in content.iter_mut() {
+                    if let Element::Inherit { parent, .. } = expr {
+                        let parent_type = parent.eval_type(typelist, logger)?;
+                        match parent_type {
+                            Type::Definition {
+                                is_struct: false,
+                                is_mixin,
+                                ..
+                            } if is_mixin || !(*is_struct || *is_mixin_class) => {
+                                parents.push(parent_type)
+                            }
+                            _ => {
+                                return Err(ZyxtError::error_4_11_0(
+                                    *parent.to_owned(),
+                                    parent_type,
+                                )
+                                .with_element(parent))
+                            }
+                        }
+                    }
+                }
+                // the class can refer to itself in its body before its attributes are checked
+                let mut unchecked_class_attrs = HashMap::new();
+                let mut unchecked_inst_attrs = HashMap::new();
+                for expr in content.iter() {
+                    if let Element::Declare {
+                        variable,
+                        content,
+                        flags,
+                        ..
+                    } = expr
+                    {
+                        if flags.contains(&Flag::Inst) {
+                            &mut unchecked_inst_attrs
+                        } else {
+                            &mut unchecked_class_attrs
+                        }
+                        .insert(variable.get_name(), *content.to_owned());
+                    }
+                }
+                let unchecked = Type::Definition {
+                    name: name.to_owned().unwrap_or_default(),
+                    is_struct: *is_struct,
+                    is_mixin: *is_mixin_class,
+                    generics: generics.to_owned(),
+                    fields: args.to_owned().unwrap_or_default(),
+                    parents: parents.to_owned(),
+                    class_attrs: unchecked_class_attrs,
+                    inst_attrs: unchecked_inst_attrs,
+                    init_attrs: Default::default(),
+                    attr_flags: Box::new(Element::attr_flags(content)),
+                };
+                if let Some(name) = name {
+                    typelist.declare_val(name, &unchecked);
+                }
+                // private and protected attributes can be used in the body of the class
+                typelist.classes.push(unchecked);
+                // `_init` is checked first, so that the attributes it gives `self` are known to the other methods
+                let (init, rest): (Vec<_>, Vec<_>) = content
+                    .iter_mut()
+                    .filter(|expr| !matches!(expr, Element::Inherit { .. }))
+                    .partition(|expr| {
+                        matches!(expr, Element::Declare { variable, .. } if variable.get_name() == "_init")
+                    });
+                typelist.init_attrs = Some(HashMap::new());
+                let checked = init
+                    .into_iter()
+                    .try_for_each(|expr| expr.eval_type(typelist, logger).map(|_| ()));
+                let init_attrs = typelist.init_attrs.take().unwrap_or_default();
+                if let Some(Type::Definition {
+                    init_attrs: unchecked_init_attrs,
+                    ..
+                }) = typelist.classes.last_mut()
+                {
+                    **unchecked_init_attrs = init_attrs.to_owned();
+                }
+                if let Some(name) = name {
+                    let unchecked = typelist.classes.last().unwrap().to_owned();
+                    typelist.declare_val(name, &unchecked);
+                }
+                let checked = checked.and_then(|_| {
+                    rest.into_iter()
+                        .try_for_each(|expr| expr.eval_type(typelist, logger).map(|_| ()))
+                });
+                typelist.classes.pop();
+                checked?;
                 for expr in content.iter_mut() {
-                    expr.eval_type(typelist)?;
                     if let Element::Declare {
                         variable,
                         content,
@@ -528,30 +2532,162 @@ impl Element {
                         ..
                     } = expr
                     {
-                        content.eval_type(typelist)?;
                         if flags.contains(&Flag::Inst) && args != &None {
-                            todo!("raise error here")
+                            return Err(
+                                ZyxtError::error_2_6_0(variable.get_name()).with_element(variable)
+                            );
                         }
                         if flags.contains(&Flag::Inst) {
                             inst_attrs.insert(variable.get_name(), *content.to_owned());
+                        } else {
+                            class_attrs.insert(variable.get_name(), *content.to_owned());
                         }
                     }
                 }
                 if args.is_some() && class_attrs.contains_key("_init") {
-                    todo!("raise error here")
+                    return Err(ZyxtError::error_2_6_1().with_pos_and_raw(position, raw));
                 }
                 typelist.pop_frame();
-                Ok(Type::Definition {
-                    name: if *is_struct { "struct" } else { "class" }.to_string(),
-                    generics: vec![],
-                    class_attrs,
+                for attr in class_attrs.values_mut() {
+                    if let Element::Procedure {
+                        args, return_type, ..
+                    } = attr
+                    {
+                        for arg in args.iter_mut() {
+                            Element::unresolve_type(&mut arg.type_, name);
+                        }
+                        Element::unresolve_type(return_type, name);
+                    }
+                }
+                let definition = Type::Definition {
+                    name: name
+                        .get_or_insert_with(|| {
+                            if *is_struct {
+                                "struct"
+                            } else if *is_mixin_class {
+                                "mixin"
+                            } else {
+                                "class"
+                            }
+                            .to_string()
+                        })
+                        .to_owned(),
+                    is_struct: *is_struct,
+                    is_mixin: *is_mixin_class,
+                    generics: generics.to_owned(),
+                    fields: args.to_owned().unwrap_or_default(),
+                    parents,
+                    class_attrs: class_attrs.to_owned(),
                     inst_attrs: inst_attrs.to_owned(),
+                    init_attrs: Box::new(init_attrs),
+                    attr_flags: Box::new(Element::attr_flags(content)),
+                };
+                if definition.mro().is_none() {
+                    return Err(ZyxtError::error_4_11_1().with_pos_and_raw(position, raw));
+                }
+                Ok(definition)
+            }
+            Element::Inherit { position, raw, .. } => {
+                Err(ZyxtError::error_2_6_2().with_pos_and_raw(position, raw))
+            }
+            Element::Enum { name, variants, .. } => {
+                let name = name.get_or_insert_with(|| "enum".to_string()).to_owned();
+                for (variant, value) in variants.iter_mut() {
+                    // struct variants are named after their enum
+                    if let Element::Class {
+                        name: struct_name @ None,
+                        ..
+                    } = value
+                    {
+                        *struct_name = Some(format!("{}.{}", name, variant));
+                    }
+                    let value_type = value.eval_type(typelist, logger)?;
+                    if !matches!(
+                        value,
+                        Element::Class {
+                            is_struct: true,
+                            ..
+                        }
+                    ) && value_type != Type::null()
+                        && !value_type.is_num()
+                        && !["str", "bool"].contains(&&*value_type.to_string())
+                    {
+                        return Err(ZyxtError::error_4_12(variant.to_owned(), value_type)
+                            .with_element(value));
+                    }
+                }
+                Ok(Type::Enum {
+                    name,
+                    variants: variants.to_owned(),
                 })
             }
-            Element::NullElement
-            | Element::Delete { .. }
-            | Element::Comment { .. }
-            | Element::Return { .. } => Ok(Type::null()),
+            Element::Return {
+                value,
+                label,
+                position,
+                raw,
+            } => {
+                let value_type = value.eval_type(typelist, logger)?;
+                let scope = if let Some(label) = label {
+                    let scope = typelist.labels.iter_mut().rev().find(|scope| {
+                        scope.kind == LabelKind::Proc && scope.label.as_ref() == Some(label)
+                    });
+                    if scope.is_none() {
+                        return Err(
+                            ZyxtError::error_3_2(label.to_owned()).with_pos_and_raw(position, raw)
+                        );
+                    }
+                    scope
+                } else {
+                    typelist
+                        .labels
+                        .iter_mut()
+                        .rev()
+                        .find(|scope| scope.kind != LabelKind::Loop)
+                };
+                if let Some(LabelData { return_type, .. }) = scope {
+                    if let Some(return_type) = return_type {
+                        // returning null from a block that otherwise returns T makes it return `T?`
+                        if let Some(common_type) = return_type.or_null(&value_type) {
+                            *return_type = common_type;
+                        } else {
+                            return Err(ZyxtError::error_4_t(return_type.to_owned(), value_type)
+                                .with_pos_and_raw(position, raw));
+                        }
+                    } else {
+                        *return_type = Some(value_type.to_owned());
+                    }
+                }
+                Ok(Type::Return(Box::new(value_type)))
+            }
+            Element::Break {
+                label,
+                position,
+                raw,
+            } => Element::loop_label_type("break", label, typelist, position, raw),
+            Element::Continue {
+                label,
+                position,
+                raw,
+            } => Element::loop_label_type("continue", label, typelist, position, raw),
+            Element::Delete {
+                names,
+                position,
+                raw,
+            } => {
+                if let Some(name) = names.iter().find(|name| typelist.is_const(name)) {
+                    return Err(
+                        ZyxtError::error_3_4(name.to_owned()).with_pos_and_raw(position, raw)
+                    );
+                }
+                if let Some(name) = names.iter().find(|name| typelist.is_referenced(name)) {
+                    return Err(
+                        ZyxtError::error_3_7(name.to_owned()).with_pos_and_raw(position, raw)
+                    );
+                }
+                Ok(Type::null())
+            }
+            Element::NullElement | Element::Comment { .. } => Ok(Type::null()),
             Element::Token(Token {
                 position, value, ..
             }) => Err(ZyxtError::error_2_1_0(value.to_owned()).with_pos_and_raw(position, value)),
@@ -1,11 +1,17 @@
 use crate::errors::ZyxtError;
 use crate::interpreter::interpret_block;
+use crate::objects::control_flow::ControlFlow;
 use crate::objects::position::Position;
 use crate::objects::typeobj::Type;
 use crate::objects::value::Value;
 use crate::Element;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
+use std::rc::Rc;
+
+/// How many procedure calls deep the interpreter can go by default
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
 
 const PRIM_NAMES: [&str; 22] = [
     "str", "bool", "i8", "i16", "i32", "i64", "i128", "isize", "ibig", "u8", "u16", "u32", "u64",
@@ -35,27 +41,109 @@ impl Print for StdIoPrint {
     }
 }
 
+/// A scope of variables. Frames are shared, so that procedures can keep the frames they were defined in
+pub type Frame<T> = Rc<RefCell<HashMap<String, T>>>;
+
+/// The frames a procedure was defined in, which its free variables are resolved from
+#[derive(Clone, Default)]
+pub struct Environment(pub Vec<Frame<Value>>);
+impl PartialEq for Environment {
+    fn eq(&self, other: &Self) -> bool {
+        // frames may contain the procedure itself, so they are compared by identity
+        self.0.len() == other.0.len() && self.0.iter().zip(&other.0).all(|(a, b)| Rc::ptr_eq(a, b))
+    }
+}
+
+/// A variable in a frame, which references and pointers to it alias
+#[derive(Clone)]
+pub struct Slot {
+    pub frame: Frame<Value>,
+    pub name: String,
+}
+impl PartialEq for Slot {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.frame, &other.frame) && self.name == other.name
+    }
+}
+impl Slot {
+    /// The value in the slot, or None if the variable has been deleted
+    pub fn get(&self) -> Option<Value> {
+        self.frame.borrow().get(&self.name).cloned()
+    }
+    pub fn set(&self, value: Value) {
+        self.frame.borrow_mut().insert(self.name.to_owned(), value);
+    }
+}
+
 pub struct FrameData<T: Clone + Display> {
     pub position: Position,
     pub raw_call: String,
     pub args: HashMap<String, T>,
 }
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LabelKind {
+    Loop,
+    Block,
+    Proc,
+}
+pub struct LabelData {
+    pub kind: LabelKind,
+    pub label: Option<String>,
+    pub return_type: Option<Type>,
+}
 pub struct InterpreterData<'a, T: Clone + Display, O: Print> {
-    pub heap: Vec<HashMap<String, T>>,
+    pub heap: Vec<Frame<T>>,
+    /// The names declared `const` in each frame
+    pub consts: Vec<HashSet<String>>,
+    /// Frames below this index can be read but not changed
+    pub read_only_depth: usize,
+    /// The procedures in each frame that are declared ahead of their declaration, for the bodies of procedures to call
+    pub predeclared: Vec<HashSet<String>>,
+    /// Frames below this index are outside the body of the procedure being checked
+    pub proc_depth: usize,
+    /// The procedures each procedure in each frame uses before they are declared, which have to be declared where it is called
+    pub forward_refs: Vec<HashMap<String, HashSet<String>>>,
+    /// The procedures used before they are declared by the bodies of the procedures being declared, innermost last
+    pub pending_forward_refs: Vec<HashSet<String>>,
+    /// The variables in each frame that references or pointers refer to, which cannot be redeclared or deleted
+    pub referenced: Vec<HashSet<String>>,
+    /// The types variables are narrowed to in each frame, which reading them gives but assigning to them does not
+    pub narrowed: Vec<HashMap<String, T>>,
     pub defer: Vec<Vec<Vec<Element>>>,
     pub frame_data: Vec<Option<FrameData<T>>>,
+    pub labels: Vec<LabelData>,
+    /// The definitions of the classes whose bodies are being checked, innermost last
+    pub classes: Vec<Type>,
+    /// The types of the attributes assigned to `self` in the `_init` being checked
+    pub init_attrs: Option<HashMap<String, Type>>,
+    /// How many procedure calls deep the interpreter currently is
+    pub call_depth: usize,
+    pub max_call_depth: usize,
     pub out: &'a mut O,
 }
 impl<'a, O: Print> InterpreterData<'a, Value, O> {
     pub fn default_variable(out: &'a mut O) -> InterpreterData<'a, Value, O> {
         let mut v = InterpreterData {
-            heap: vec![HashMap::new()],
+            heap: vec![Default::default()],
+            consts: vec![Default::default()],
+            read_only_depth: 0,
+            predeclared: vec![Default::default()],
+            proc_depth: 0,
+            forward_refs: vec![Default::default()],
+            pending_forward_refs: vec![],
+            narrowed: vec![Default::default()],
+            referenced: vec![Default::default()],
             defer: vec![vec![]],
             frame_data: vec![],
+            labels: vec![],
+            classes: vec![],
+            init_attrs: None,
+            call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
             out,
         };
         for t in PRIM_NAMES {
-            v.heap[0].insert(
+            v.heap[0].borrow_mut().insert(
                 t.to_string(),
                 Value::Type(Type::Instance {
                     name: t.to_string(),
@@ -68,11 +156,40 @@ impl<'a, O: Print> InterpreterData<'a, Value, O> {
         v.add_frame(None);
         v
     }
+    /// Creates interpreter data that runs in a procedure's environment, with the environment read-only
+    pub fn from_environment(
+        environment: Environment,
+        out: &'a mut O,
+    ) -> InterpreterData<'a, Value, O> {
+        let depth = environment.0.len();
+        let mut v = InterpreterData {
+            heap: environment.0,
+            consts: (0..depth).map(|_| Default::default()).collect(),
+            read_only_depth: depth,
+            predeclared: (0..depth).map(|_| Default::default()).collect(),
+            proc_depth: 0,
+            forward_refs: (0..depth).map(|_| Default::default()).collect(),
+            pending_forward_refs: vec![],
+            narrowed: (0..depth).map(|_| Default::default()).collect(),
+            referenced: (0..depth).map(|_| Default::default()).collect(),
+            defer: (0..depth).map(|_| vec![]).collect(),
+            frame_data: (0..depth).map(|_| None).collect(),
+            labels: vec![],
+            classes: vec![],
+            init_attrs: None,
+            call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            out,
+        };
+        v.add_frame(None);
+        v
+    }
     pub fn heap_to_string(&self) -> String {
         self.heap
             .iter()
             .map(|hmap| {
-                hmap.iter()
+                hmap.borrow()
+                    .iter()
                     .map(|(k, v)| format!("{}: {} = {}", k, v.get_type_obj(), v))
                     .collect::<Vec<String>>()
                     .join("\n")
@@ -80,31 +197,72 @@ impl<'a, O: Print> InterpreterData<'a, Value, O> {
             .collect::<Vec<String>>()
             .join("\n-------\n")
     }
-    pub fn pop_frame(&mut self) -> Result<Option<Value>, ZyxtError> {
-        self.heap.pop();
-        self.frame_data.pop();
-
+    /// The slot of the variable a name refers to, for a reference or pointer to alias
+    pub fn get_slot(
+        &self,
+        name: &String,
+        position: &Position,
+        raw: &String,
+    ) -> Result<Slot, ZyxtError> {
+        self.heap
+            .iter()
+            .rev()
+            .find(|set| set.borrow().contains_key(name))
+            .map(|frame| Slot {
+                frame: frame.to_owned(),
+                name: name.to_owned(),
+            })
+            .ok_or_else(|| ZyxtError::error_3_0(name.to_owned()).with_pos_and_raw(position, raw))
+    }
+    /// Gets the instance a method was called on, from the argument it was bound to in the innermost frame
+    pub fn get_self(&self, name: &Option<String>) -> Option<Value> {
+        let name = name.as_ref()?;
+        self.heap.last()?.borrow().get(name).cloned()
+    }
+    pub fn pop_frame(&mut self) -> Result<(), ControlFlow> {
+        // defer blocks run before the frame's variables go out of scope
+        let mut res = Ok(());
         for content in self.defer.last().unwrap().clone() {
-            if let Value::Return(v) = interpret_block(&content, self, false, false)? {
-                self.defer.pop();
-                return Ok(Some(*v));
+            if let Err(e) = interpret_block(&content, self, false) {
+                res = Err(e);
+                break;
             }
         }
+        self.heap.pop();
+        self.consts.pop();
+        self.predeclared.pop();
+        self.forward_refs.pop();
+        self.narrowed.pop();
+        self.referenced.pop();
+        self.frame_data.pop();
         self.defer.pop();
-        Ok(None)
+        res
     }
 }
 
 impl<'a, O: Print> InterpreterData<'a, Type, O> {
     pub fn default_type(out: &'a mut O) -> InterpreterData<'a, Type, O> {
         let mut v = InterpreterData {
-            heap: vec![HashMap::new()],
+            heap: vec![Default::default()],
+            consts: vec![Default::default()],
+            read_only_depth: 0,
+            predeclared: vec![Default::default()],
+            proc_depth: 0,
+            forward_refs: vec![Default::default()],
+            pending_forward_refs: vec![],
+            narrowed: vec![Default::default()],
+            referenced: vec![Default::default()],
             defer: vec![vec![]],
             frame_data: vec![],
+            labels: vec![],
+            classes: vec![],
+            init_attrs: None,
+            call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
             out,
         };
         for t in PRIM_NAMES {
-            v.heap[0].insert(
+            v.heap[0].borrow_mut().insert(
                 t.to_string(),
                 Type::Instance {
                     name: "type".to_string(),
@@ -119,6 +277,11 @@ impl<'a, O: Print> InterpreterData<'a, Type, O> {
     }
     pub fn pop_frame(&mut self) {
         self.heap.pop();
+        self.consts.pop();
+        self.predeclared.pop();
+        self.forward_refs.pop();
+        self.narrowed.pop();
+        self.referenced.pop();
         self.frame_data.pop();
         self.defer.pop();
     }
@@ -126,16 +289,120 @@ impl<'a, O: Print> InterpreterData<'a, Type, O> {
 
 impl<T: Clone + Display, O: Print> InterpreterData<'_, T, O> {
     pub fn add_frame(&mut self, frame_data: Option<FrameData<T>>) {
-        self.heap.push(HashMap::new());
+        self.heap.push(Default::default());
+        self.consts.push(Default::default());
+        self.predeclared.push(Default::default());
+        self.forward_refs.push(Default::default());
+        self.narrowed.push(Default::default());
+        self.referenced.push(Default::default());
         self.defer.push(vec![]);
         self.frame_data.push(frame_data);
     }
     pub fn declare_val(&mut self, name: &str, value: &T) {
         self.heap
+            .last()
+            .unwrap()
+            .borrow_mut()
+            .insert(name.to_string(), value.to_owned());
+        self.consts.last_mut().unwrap().remove(name);
+        self.predeclared.last_mut().unwrap().remove(name);
+        self.forward_refs.last_mut().unwrap().remove(name);
+    }
+    /// Declares a procedure in the innermost frame ahead of its declaration, so that only the bodies of procedures can refer to it
+    pub fn predeclare_val(&mut self, name: &str, value: &T) {
+        self.declare_val(name, value);
+        self.predeclared
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string());
+    }
+    /// Narrows the type of a variable in the innermost frame, for reading it
+    pub fn narrow(&mut self, name: &str, value: &T) {
+        self.narrowed
             .last_mut()
             .unwrap()
             .insert(name.to_string(), value.to_owned());
     }
+    /// Undoes the narrowing of a variable, after something else is assigned to it
+    pub fn unnarrow(&mut self, name: &String) {
+        if let Some(i) = self
+            .heap
+            .iter()
+            .rposition(|set| set.borrow().contains_key(name))
+        {
+            for narrowed in &mut self.narrowed[i..] {
+                narrowed.remove(name);
+            }
+        }
+    }
+    /// Marks the variable a name refers to as referred to by a reference or pointer
+    pub fn mark_referenced(&mut self, name: &String) {
+        if let Some(i) = self
+            .heap
+            .iter()
+            .rposition(|set| set.borrow().contains_key(name))
+        {
+            self.referenced[i].insert(name.to_owned());
+        }
+    }
+    /// Whether a variable in the innermost frame is referred to by a reference or pointer
+    pub fn is_referenced(&self, name: &String) -> bool {
+        self.referenced.last().unwrap().contains(name)
+    }
+    /// Whether a name refers to a procedure that is not declared yet where it is used
+    fn is_predeclared(&self, name: &String) -> bool {
+        self.heap
+            .iter()
+            .rposition(|set| set.borrow().contains_key(name))
+            .is_some_and(|i| i >= self.proc_depth && self.predeclared[i].contains(name))
+    }
+    /// Notes a procedure used before it is declared, in the body of the procedure being declared
+    fn note_forward_ref(&mut self, name: &String) {
+        let is_forward_ref = self
+            .heap
+            .iter()
+            .rposition(|set| set.borrow().contains_key(name))
+            .is_some_and(|i| self.predeclared[i].contains(name));
+        if let (true, Some(refs)) = (is_forward_ref, self.pending_forward_refs.last_mut()) {
+            refs.insert(name.to_owned());
+        }
+    }
+    /// Checks that the procedures a procedure uses before their declarations, and the ones they use in turn,
+    /// are declared where it is called
+    pub fn check_forward_refs(
+        &mut self,
+        name: &String,
+        position: &Position,
+        raw: &String,
+    ) -> Result<(), ZyxtError> {
+        let mut to_check = vec![name.to_owned()];
+        let mut checked = HashSet::new();
+        while let Some(proc) = to_check.pop() {
+            if !checked.insert(proc.to_owned()) {
+                continue;
+            }
+            let forward_refs = self
+                .heap
+                .iter()
+                .rposition(|set| set.borrow().contains_key(&proc))
+                .and_then(|i| self.forward_refs[i].get(&proc).cloned())
+                .unwrap_or_default();
+            for forward_ref in forward_refs {
+                if self.is_predeclared(&forward_ref) {
+                    return Err(ZyxtError::error_3_8(name.to_owned(), forward_ref)
+                        .with_pos_and_raw(position, raw));
+                }
+                // a procedure called in the body of another is only called when that one is
+                self.note_forward_ref(&forward_ref);
+                to_check.push(forward_ref);
+            }
+        }
+        Ok(())
+    }
+    /// Marks a variable declared in the innermost frame as constant, until it is declared again
+    pub fn mark_const(&mut self, name: &str) {
+        self.consts.last_mut().unwrap().insert(name.to_string());
+    }
     pub fn set_val(
         &mut self,
         name: &String,
@@ -143,9 +410,15 @@ impl<T: Clone + Display, O: Print> InterpreterData<'_, T, O> {
         position: &Position,
         raw: &String,
     ) -> Result<(), ZyxtError> {
-        for set in self.heap.iter_mut().rev() {
-            if set.contains_key(name) {
-                set.insert(name.to_owned(), value.to_owned());
+        if self.is_predeclared(name) {
+            return Err(ZyxtError::error_3_0(name.to_owned()).with_pos_and_raw(position, raw));
+        }
+        if self.is_read_only(name) {
+            return Err(ZyxtError::error_3_3(name.to_owned()).with_pos_and_raw(position, raw));
+        }
+        for set in self.heap.iter().rev() {
+            if set.borrow().contains_key(name) {
+                set.borrow_mut().insert(name.to_owned(), value.to_owned());
                 return Ok(());
             }
         }
@@ -157,9 +430,31 @@ impl<T: Clone + Display, O: Print> InterpreterData<'_, T, O> {
         position: &Position,
         raw: &String,
     ) -> Result<T, ZyxtError> {
+        if self.is_predeclared(name) {
+            return Err(ZyxtError::error_3_0(name.to_owned()).with_pos_and_raw(position, raw));
+        }
+        self.note_forward_ref(name);
+        for (set, narrowed) in self.heap.iter().zip(&self.narrowed).rev() {
+            if let Some(v) = narrowed.get(name).or(set.borrow().get(name)) {
+                return Ok(v.to_owned());
+            }
+        }
+        Err(ZyxtError::error_3_0(name.to_owned()).with_pos_and_raw(position, raw))
+    }
+    /// The value of a variable as it was declared, without any narrowing
+    pub fn get_declared_val(
+        &mut self,
+        name: &String,
+        position: &Position,
+        raw: &String,
+    ) -> Result<T, ZyxtError> {
+        if self.is_predeclared(name) {
+            return Err(ZyxtError::error_3_0(name.to_owned()).with_pos_and_raw(position, raw));
+        }
+        self.note_forward_ref(name);
         for set in self.heap.iter().rev() {
-            if set.contains_key(name) {
-                return Ok(set.get(name).unwrap().to_owned());
+            if let Some(v) = set.borrow().get(name) {
+                return Ok(v.to_owned());
             }
         }
         Err(ZyxtError::error_3_0(name.to_owned()).with_pos_and_raw(position, raw))
@@ -170,12 +465,37 @@ impl<T: Clone + Display, O: Print> InterpreterData<'_, T, O> {
         position: &Position,
         raw: &String,
     ) -> Result<T, ZyxtError> {
-        if let Some(v) = self.heap.last_mut().unwrap().remove(name) {
+        if self.is_read_only(name) {
+            return Err(ZyxtError::error_3_3(name.to_owned()).with_pos_and_raw(position, raw));
+        }
+        if let Some(v) = self.heap.last().unwrap().borrow_mut().remove(name) {
             Ok(v)
         } else {
             Err(ZyxtError::error_3_0(name.to_owned()).with_pos_and_raw(position, raw))
         }
     }
+    /// Whether a variable is declared where it is used, which builtins of the same name give way to
+    pub fn is_declared(&self, name: &String) -> bool {
+        self.heap.iter().any(|set| set.borrow().contains_key(name)) && !self.is_predeclared(name)
+    }
+    /// Whether the variable a name refers to is in a read-only frame
+    pub fn is_read_only(&self, name: &String) -> bool {
+        self.heap
+            .iter()
+            .rposition(|set| set.borrow().contains_key(name))
+            .is_some_and(|i| i < self.read_only_depth)
+    }
+    /// Whether the variable a name refers to was declared `const`
+    pub fn is_const(&self, name: &String) -> bool {
+        self.heap
+            .iter()
+            .rposition(|set| set.borrow().contains_key(name))
+            .is_some_and(|i| {
+                self.consts
+                    .get(i)
+                    .is_some_and(|consts| consts.contains(name))
+            })
+    }
     pub fn add_defer(&mut self, content: Vec<Element>) {
         self.defer.last_mut().unwrap().push(content);
     }
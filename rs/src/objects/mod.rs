@@ -1,3 +1,4 @@
+pub mod control_flow;
 pub mod element;
 pub mod interpreter_data;
 pub mod position;
@@ -36,11 +36,12 @@ pub fn get_order(opr: &OprType) -> u8 {
         | OprType::MinusSign
         | OprType::Not
         | OprType::Ref
-        | OprType::Deref => 1,
+        | OprType::Pointer
+        | OprType::Deref
+        | OprType::Assert => 1,
         OprType::TypeCast => 2,
         OprType::Power => 3,
-        //OprType::Root |
-        //OprType::Logarithm => 4,
+        OprType::Root | OprType::Logarithm => 4,
         OprType::DotMult => 5,
         OprType::AstMult
         | OprType::FractDiv
@@ -54,6 +55,7 @@ pub fn get_order(opr: &OprType) -> u8 {
         | OprType::CeilDiv
         | OprType::RoundDiv => 7,
         OprType::Plus | OprType::Minus | OprType::PlusMinus | OprType::MinusPlus => 8,
+        OprType::Elvis | OprType::ErrorHandle => 9,
         OprType::Gt
         | OprType::Lt
         | OprType::Gteq
@@ -73,6 +75,14 @@ pub fn get_order(opr: &OprType) -> u8 {
         OprType::Swap => 19,
     }
 }
+/// The order of infix procedures declared without one, between arithmetic and comparison operators
+pub const DEFAULT_INFIX_ORDER: u8 = 9;
+/// Whether a chain of operators of the same order is grouped from the left, `(a - b) - c`, or the right
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[allow(dead_code)]
 pub enum OprType {
@@ -82,6 +92,8 @@ pub enum OprType {
     MinusSign,
     Not,
     Power,
+    Root,
+    Logarithm,
     DotMult,
     AstMult,
     CrossMult,
@@ -116,13 +128,133 @@ pub enum OprType {
     Concat,
     Swap,
     Ref,
+    Pointer,
     Deref,
     TypeCast,
+    Elvis,
+    ErrorHandle,
+    Assert,
     Null,
 }
+impl OprType {
+    /// The name of the magic method a class defines to overload the operator, if it can be overloaded
+    pub fn magic_method(&self) -> Option<&'static str> {
+        Some(match self {
+            OprType::Increment => "_inc",
+            OprType::Decrement => "_dec",
+            OprType::PlusSign => "_pos",
+            OprType::MinusSign => "_neg",
+            OprType::Not => "_not",
+            OprType::Power => "_pow",
+            OprType::Root => "_rt",
+            OprType::Logarithm => "_log",
+            OprType::DotMult => "_dot_mul",
+            OprType::AstMult => "_mul",
+            OprType::CrossMult => "_cross_mul",
+            OprType::Div => "_div",
+            OprType::FloorDiv => "_floor_div",
+            OprType::CeilDiv => "_ceil_div",
+            OprType::RoundDiv => "_round_div",
+            OprType::FractDiv => "_fract_div",
+            OprType::FloorfractDiv => "_floor_fract_div",
+            OprType::CeilfractDiv => "_ceil_fract_div",
+            OprType::RoundfractDiv => "_round_fract_div",
+            OprType::Modulo => "_mod",
+            OprType::Plus => "_add",
+            OprType::Minus => "_sub",
+            OprType::PlusMinus => "_add_sub",
+            OprType::MinusPlus => "_sub_add",
+            OprType::Xor => "_xor",
+            OprType::Gt => "_gt",
+            OprType::Lt => "_lt",
+            OprType::Gteq => "_ge",
+            OprType::Lteq => "_le",
+            OprType::Eq => "_eq",
+            OprType::Noteq => "_ne",
+            OprType::Iseq => "_iseq",
+            OprType::Isnteq => "_isnteq",
+            OprType::Concat => "_concat",
+            OprType::TypeCast => "_cast",
+            OprType::And => "_and",
+            OprType::Or => "_or",
+            // null-checking, error-handling, identity and reference operators are never overloaded
+            OprType::Elvis
+            | OprType::ErrorHandle
+            | OprType::Assert
+            | OprType::Istype
+            | OprType::Isnttype
+            | OprType::Is
+            | OprType::Isnt
+            | OprType::Swap
+            | OprType::Ref
+            | OprType::Pointer
+            | OprType::Deref
+            | OprType::Null => return None,
+        })
+    }
+    /// The name of the magic method called on the right operand when the left one does not overload the operator,
+    /// eg `_radd` for `_add`
+    pub fn reflected_magic_method(&self) -> Option<String> {
+        self.magic_method()
+            .map(|name| format!("_r{}", name.trim_start_matches('_')))
+    }
+}
 impl Display for OprType {
+    /// Writes the operator as it is spelt
     fn fmt(&self, f: &mut Formatter) -> Result {
-        write!(f, "{:?}", self)
+        write!(
+            f,
+            "{}",
+            match self {
+                OprType::Increment => "++",
+                OprType::Decrement => "--",
+                OprType::PlusSign | OprType::Plus => "+",
+                OprType::MinusSign | OprType::Minus => "-",
+                OprType::Not => "!",
+                OprType::Power => "^",
+                OprType::Root => "rt",
+                OprType::Logarithm => "log",
+                OprType::DotMult => "·",
+                OprType::AstMult => "*",
+                OprType::CrossMult => "×",
+                OprType::Div => "÷",
+                OprType::FloorDiv => "÷f",
+                OprType::CeilDiv => "÷c",
+                OprType::RoundDiv => "÷~",
+                OprType::FractDiv => "/",
+                OprType::FloorfractDiv => "/f",
+                OprType::CeilfractDiv => "/c",
+                OprType::RoundfractDiv => "/~",
+                OprType::Modulo => "%",
+                OprType::PlusMinus => "±",
+                OprType::MinusPlus => "∓",
+                OprType::And => "&&",
+                OprType::Or => "||",
+                OprType::Xor => "^^",
+                OprType::Gt => ">",
+                OprType::Lt => "<",
+                OprType::Gteq => ">=",
+                OprType::Lteq => "<=",
+                OprType::Eq => "==",
+                OprType::Noteq => "!=",
+                OprType::Istype => "istype",
+                OprType::Isnttype => "!istype",
+                OprType::Is => "is",
+                OprType::Isnt => "!is",
+                OprType::Iseq => "===",
+                OprType::Isnteq => "!==",
+                OprType::Concat => "~",
+                OprType::Swap => "><",
+                OprType::Ref => "&",
+                OprType::Pointer => "&>",
+                OprType::Deref => "\\",
+                OprType::TypeCast => "@",
+                OprType::Elvis => "?:",
+                OprType::ErrorHandle => "!?:",
+                OprType::Assert => "!!",
+                OprType::Null => "",
+            }
+        )
     }
 }
 
@@ -141,9 +273,15 @@ pub enum Keyword {
     If,
     Else,
     Elif,
+    Match,
+    Of,
     Do,
     While,
     For,
+    In,
+    Loop,
+    Break,
+    Continue,
     Delete,
     Return,
     Proc,
@@ -152,6 +290,9 @@ pub enum Keyword {
     Defer,
     Class,
     Struct,
+    Mixin,
+    Enum,
+    Infix,
 }
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Side {
@@ -161,15 +302,17 @@ pub enum Side {
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum TokenType {
-    CommentStart,            // //
-    CommentEnd,              // \n
-    MultilineCommentStart,   // /*
-    MultilineCommentEnd,     // */
-    Flag(Flag),              // hoi, pub, priv, prot, const
-    UnaryOpr(OprType, Side), // \~, ++, ! etc
-    AssignmentOpr(OprType),  // =, += etc
+    CommentStart,                 // //
+    CommentEnd,                   // \n
+    MultilineCommentStart,        // /*
+    MultilineCommentEnd,          // */
+    Flag(Flag),                   // hoi, pub, priv, prot, const
+    UnaryOpr(OprType, Side),      // \~, ++, ! etc
+    AssignmentOpr(OprType),       // =, += etc
     NormalOpr(OprType), // +, -, /f, rt, \&, ==, >, is, &&, ||, ^^, .., ><, istype, isnttype etc
     DotOpr,             // .
+    SafeDotOpr,         // ?.
+    RangeOpr,           // ..
     DeclarationOpr,     // :=
     LiteralMisc,        // true, null, etc
     LiteralNumber,      // 3, 24, -34.5 etc
@@ -188,7 +331,10 @@ pub enum TokenType {
     Apostrophe,         // '
     Quote,              // "
     Bar,                // |
+    QuestionMark,       // ?
+    ErrorableMark,      // !?
     Keyword(Keyword),   // if, while etc
+    InfixProc(u8, Associativity), // a variable used infix where an infix procedure of its name is declared
     Comment,
     Variable,
     Whitespace,
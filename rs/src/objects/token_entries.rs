@@ -156,6 +156,11 @@ pub fn singular_token_entries() -> Vec<SingularTokenEntry<'static>> {
             type_: TokenType::Colon,
             ..Default::default()
         },
+        SingularTokenEntry {
+            value: '\'',
+            type_: TokenType::Apostrophe,
+            ..Default::default()
+        },
         SingularTokenEntry {
             value: '\"',
             type_: TokenType::Quote,
@@ -284,6 +289,11 @@ pub fn singular_token_entries() -> Vec<SingularTokenEntry<'static>> {
             type_: TokenType::Bar,
             ..Default::default()
         },
+        SingularTokenEntry {
+            value: '?',
+            type_: TokenType::QuestionMark,
+            ..Default::default()
+        },
     ]
 }
 
@@ -376,6 +386,65 @@ pub fn compound_token_entries_1() -> Vec<CompoundTokenEntry<'static>> {
             ],
             ..Default::default()
         },
+        CompoundTokenEntry {
+            value: "?.",
+            type_: TokenType::SafeDotOpr,
+            combination: &[
+                Pattern::Token(TokenType::QuestionMark),
+                Pattern::Token(TokenType::DotOpr),
+            ],
+            categories: &[TokenCategory::Operator],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "?:",
+            type_: TokenType::NormalOpr(OprType::Elvis),
+            combination: &[
+                Pattern::Token(TokenType::QuestionMark),
+                Pattern::Token(TokenType::Colon),
+            ],
+            categories: &[TokenCategory::Operator],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "!?",
+            type_: TokenType::ErrorableMark,
+            combination: &[
+                Pattern::Token(TokenType::UnaryOpr(OprType::Not, Side::Left)),
+                Pattern::Token(TokenType::QuestionMark),
+            ],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "!?:",
+            type_: TokenType::NormalOpr(OprType::ErrorHandle),
+            combination: &[
+                Pattern::Token(TokenType::ErrorableMark),
+                Pattern::Token(TokenType::Colon),
+            ],
+            categories: &[TokenCategory::Operator],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "!!",
+            type_: TokenType::UnaryOpr(OprType::Assert, Side::Right),
+            combination: &[
+                Pattern::Token(TokenType::UnaryOpr(OprType::Not, Side::Left)),
+                Pattern::Token(TokenType::UnaryOpr(OprType::Not, Side::Left)),
+            ],
+            categories: &[TokenCategory::Operator, TokenCategory::ValueEnd],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "..",
+            type_: TokenType::RangeOpr,
+            combination: &[
+                Pattern::Token(TokenType::DotOpr),
+                Pattern::Token(TokenType::DotOpr),
+            ],
+            categories: &[TokenCategory::Operator],
+            ..Default::default()
+        },
         CompoundTokenEntry {
             value: "+-",
             type_: TokenType::NormalOpr(OprType::PlusMinus),
@@ -388,7 +457,7 @@ pub fn compound_token_entries_1() -> Vec<CompoundTokenEntry<'static>> {
         },
         CompoundTokenEntry {
             value: "-+",
-            type_: TokenType::NormalOpr(OprType::PlusMinus),
+            type_: TokenType::NormalOpr(OprType::MinusPlus),
             combination: &[
                 Pattern::Token(TokenType::NormalOpr(OprType::Minus)),
                 Pattern::Token(TokenType::NormalOpr(OprType::Plus)),
@@ -508,7 +577,7 @@ pub fn compound_token_entries_1() -> Vec<CompoundTokenEntry<'static>> {
         },
         CompoundTokenEntry {
             value: "/c=",
-            type_: TokenType::AssignmentOpr(OprType::FloorfractDiv),
+            type_: TokenType::AssignmentOpr(OprType::CeilfractDiv),
             combination: &[
                 Pattern::Token(TokenType::NormalOpr(OprType::CeilfractDiv)),
                 Pattern::Token(TokenType::AssignmentOpr(OprType::Null)),
@@ -518,7 +587,7 @@ pub fn compound_token_entries_1() -> Vec<CompoundTokenEntry<'static>> {
         },
         CompoundTokenEntry {
             value: "/~=",
-            type_: TokenType::AssignmentOpr(OprType::FloorfractDiv),
+            type_: TokenType::AssignmentOpr(OprType::RoundfractDiv),
             combination: &[
                 Pattern::Token(TokenType::NormalOpr(OprType::RoundfractDiv)),
                 Pattern::Token(TokenType::AssignmentOpr(OprType::Null)),
@@ -616,9 +685,19 @@ pub fn compound_token_entries_1() -> Vec<CompoundTokenEntry<'static>> {
             categories: &[TokenCategory::Operator],
             ..Default::default()
         },
+        CompoundTokenEntry {
+            value: "&>",
+            type_: TokenType::UnaryOpr(OprType::Pointer, Side::Left),
+            combination: &[
+                Pattern::Token(TokenType::UnaryOpr(OprType::Ref, Side::Left)),
+                Pattern::Token(TokenType::NormalOpr(OprType::Gt)),
+            ],
+            categories: &[TokenCategory::Operator, TokenCategory::ValueStart],
+            ..Default::default()
+        },
         CompoundTokenEntry {
             value: "||",
-            type_: TokenType::NormalOpr(OprType::And),
+            type_: TokenType::NormalOpr(OprType::Or),
             combination: &[
                 Pattern::Token(TokenType::Bar),
                 Pattern::Token(TokenType::Bar),
@@ -728,6 +807,18 @@ pub fn compound_token_entries_2() -> Vec<CompoundTokenEntry<'static>> {
             combination: &[Pattern::Value(TokenType::Variable, "elif")],
             ..Default::default()
         },
+        CompoundTokenEntry {
+            value: "match",
+            type_: TokenType::Keyword(Keyword::Match),
+            combination: &[Pattern::Value(TokenType::Variable, "match")],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "of",
+            type_: TokenType::Keyword(Keyword::Of),
+            combination: &[Pattern::Value(TokenType::Variable, "of")],
+            ..Default::default()
+        },
         CompoundTokenEntry {
             value: "do",
             type_: TokenType::Keyword(Keyword::Do),
@@ -746,6 +837,30 @@ pub fn compound_token_entries_2() -> Vec<CompoundTokenEntry<'static>> {
             combination: &[Pattern::Value(TokenType::Variable, "for")],
             ..Default::default()
         },
+        CompoundTokenEntry {
+            value: "in",
+            type_: TokenType::Keyword(Keyword::In),
+            combination: &[Pattern::Value(TokenType::Variable, "in")],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "loop",
+            type_: TokenType::Keyword(Keyword::Loop),
+            combination: &[Pattern::Value(TokenType::Variable, "loop")],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "break",
+            type_: TokenType::Keyword(Keyword::Break),
+            combination: &[Pattern::Value(TokenType::Variable, "break")],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "continue",
+            type_: TokenType::Keyword(Keyword::Continue),
+            combination: &[Pattern::Value(TokenType::Variable, "continue")],
+            ..Default::default()
+        },
         CompoundTokenEntry {
             value: "del",
             type_: TokenType::Keyword(Keyword::Delete),
@@ -760,6 +875,13 @@ pub fn compound_token_entries_2() -> Vec<CompoundTokenEntry<'static>> {
             categories: &[TokenCategory::Literal, TokenCategory::ValueStart],
             ..Default::default()
         },
+        CompoundTokenEntry {
+            value: "return",
+            type_: TokenType::Keyword(Keyword::Return),
+            combination: &[Pattern::Value(TokenType::Variable, "return")],
+            categories: &[TokenCategory::Literal, TokenCategory::ValueStart],
+            ..Default::default()
+        },
         CompoundTokenEntry {
             value: "proc",
             type_: TokenType::Keyword(Keyword::Proc),
@@ -795,6 +917,27 @@ pub fn compound_token_entries_2() -> Vec<CompoundTokenEntry<'static>> {
             categories: &[TokenCategory::Literal, TokenCategory::ValueStart],
             ..Default::default()
         },
+        CompoundTokenEntry {
+            value: "mixin",
+            type_: TokenType::Keyword(Keyword::Mixin),
+            combination: &[Pattern::Value(TokenType::Variable, "mixin")],
+            categories: &[TokenCategory::Literal, TokenCategory::ValueStart],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "enum",
+            type_: TokenType::Keyword(Keyword::Enum),
+            combination: &[Pattern::Value(TokenType::Variable, "enum")],
+            categories: &[TokenCategory::Literal, TokenCategory::ValueStart],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "infix",
+            type_: TokenType::Keyword(Keyword::Infix),
+            combination: &[Pattern::Value(TokenType::Variable, "infix")],
+            categories: &[TokenCategory::Literal, TokenCategory::ValueStart],
+            ..Default::default()
+        },
         CompoundTokenEntry {
             value: "defer",
             type_: TokenType::Keyword(Keyword::Defer),
@@ -803,12 +946,16 @@ pub fn compound_token_entries_2() -> Vec<CompoundTokenEntry<'static>> {
             ..Default::default()
         },
         CompoundTokenEntry {
-            value: "!is",
-            type_: TokenType::NormalOpr(OprType::Isnt),
-            combination: &[
-                Pattern::Token(TokenType::UnaryOpr(OprType::Not, Side::Left)),
-                Pattern::Value(TokenType::Variable, "is"),
-            ],
+            value: "rt",
+            type_: TokenType::NormalOpr(OprType::Root),
+            combination: &[Pattern::Value(TokenType::Variable, "rt")],
+            categories: &[TokenCategory::Operator],
+            ..Default::default()
+        },
+        CompoundTokenEntry {
+            value: "log",
+            type_: TokenType::NormalOpr(OprType::Logarithm),
+            combination: &[Pattern::Value(TokenType::Variable, "log")],
             categories: &[TokenCategory::Operator],
             ..Default::default()
         },
@@ -819,6 +966,17 @@ pub fn compound_token_entries_2() -> Vec<CompoundTokenEntry<'static>> {
             categories: &[TokenCategory::Operator],
             ..Default::default()
         },
+        CompoundTokenEntry {
+            value: "!is",
+            type_: TokenType::NormalOpr(OprType::Isnt),
+            // `is` is matched first, so that it is not matched again in `!is`
+            combination: &[
+                Pattern::Token(TokenType::UnaryOpr(OprType::Not, Side::Left)),
+                Pattern::Value(TokenType::Variable, "is"),
+            ],
+            categories: &[TokenCategory::Operator],
+            ..Default::default()
+        },
         CompoundTokenEntry {
             value: "hoi",
             type_: TokenType::Flag(Flag::Hoi),
@@ -840,6 +998,13 @@ pub fn compound_token_entries_2() -> Vec<CompoundTokenEntry<'static>> {
             categories: &[TokenCategory::ValueStart],
             ..Default::default()
         },
+        CompoundTokenEntry {
+            value: "prot",
+            type_: TokenType::Flag(Flag::Prot),
+            combination: &[Pattern::Value(TokenType::Variable, "prot")],
+            categories: &[TokenCategory::ValueStart],
+            ..Default::default()
+        },
         CompoundTokenEntry {
             value: "const",
             type_: TokenType::Flag(Flag::Const),
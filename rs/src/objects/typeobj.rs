@@ -1,5 +1,7 @@
 use crate::objects::element::Argument;
+use crate::objects::token::Flag;
 use crate::Element;
+use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
 
@@ -10,18 +12,83 @@ pub enum Type {
         name: String,
         type_args: Vec<Type>,
         inst_attrs: HashMap<String, Element>,
-        implementation: Option<&'static Type>,
+        implementation: Option<Box<Type>>,
     },
     Definition {
         // class, struct, (anything that implements a Type). Is of type <type> (Typedef)
-        name: String, // TODO inheritance
+        name: String,
+        is_struct: bool,
+        is_mixin: bool,
         generics: Vec<Argument>,
+        fields: Vec<Argument>, // the fields of a struct
+        parents: Vec<Type>,
         class_attrs: HashMap<String, Element>,
         inst_attrs: HashMap<String, Element>,
+        init_attrs: Box<HashMap<String, Type>>, // the types of the attributes `_init` gives `self`, boxed to keep types small
+        attr_flags: Box<HashMap<String, Vec<Flag>>>, // the flags the attributes were declared with, boxed like `init_attrs`
+    },
+    Enum {
+        // enum. Is of type <type> (Typedef)
+        name: String,
+        variants: IndexMap<String, Element>, // the value of each variant, a struct for struct variants or nothing
+    },
+    Nullable(
+        // T?, a T or null
+        Box<Type>,
+    ),
+    Errorable(
+        // T!?, a T or an error
+        Box<Type>,
+    ),
+    Generic(
+        // T in proc<T>, class<T> etc. Stands for the type given when the procedure is called or the class instantiated
+        String,
+    ),
+    Overload(
+        // the procedures of an overloaded procedure
+        Vec<Element>,
+    ),
+    Proc {
+        // proc<[T, ...], R> and fn<[T, ...], R>
+        is_fn: bool,
+        params: Vec<Param>,
+        return_type: Box<Type>,
     },
     Return(Box<Type>),
 }
 
+/// A parameter of a procedure type
+#[derive(Clone, Debug)]
+pub struct Param {
+    pub name: String,
+    pub type_: Type,
+    pub has_default: bool,
+}
+impl PartialEq for Param {
+    // procedures with differently named parameters are still the same type
+    fn eq(&self, other: &Self) -> bool {
+        self.type_ == other.type_ && self.has_default == other.has_default
+    }
+}
+impl Param {
+    /// The parameter with the generic types in its type replaced, as `Type::substitute` does
+    pub fn substitute(&self, bindings: &HashMap<String, Type>, unbound: Option<&Type>) -> Param {
+        Param {
+            type_: self.type_.substitute(bindings, unbound),
+            ..self.to_owned()
+        }
+    }
+}
+impl From<&Argument> for Param {
+    fn from(arg: &Argument) -> Self {
+        Param {
+            name: arg.name.to_owned(),
+            type_: arg.type_.to_owned(),
+            has_default: arg.default.is_some(),
+        }
+    }
+}
+
 impl Display for Type {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -44,7 +111,33 @@ impl Display for Type {
                     } else {
                         name.to_string()
                     },
-                Type::Definition { name, .. } => name.to_owned(),
+                Type::Definition { name, .. } | Type::Enum { name, .. } | Type::Generic(name) =>
+                    name.to_owned(),
+                Type::Proc {
+                    is_fn,
+                    params,
+                    return_type,
+                } => format!(
+                    "{}<[{}], {}>",
+                    if *is_fn { "fn" } else { "proc" },
+                    params
+                        .iter()
+                        .map(|param| param.type_.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    return_type
+                ),
+                Type::Overload(procs) => format!(
+                    "overload<{}>",
+                    procs
+                        .iter()
+                        .filter_map(|proc| proc.proc_type())
+                        .map(|proc_type| proc_type.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+                Type::Nullable(ty) => format!("{}?", ty),
+                Type::Errorable(ty) => format!("{}!?", ty),
                 Type::Return(ty) => format!("{}", ty),
             }
         )
@@ -65,6 +158,587 @@ impl Type {
     pub fn any() -> Self {
         Type::from_name("_any")
     }
+    /// The type of a value that is either of this type or null, `T?`
+    pub fn nullable(self) -> Type {
+        if matches!(self, Type::Nullable(_)) || self == Type::null() || self == Type::any() {
+            self
+        } else {
+            Type::Nullable(Box::new(self))
+        }
+    }
+    /// This type without null, eg T for `T?`
+    pub fn non_null(&self) -> Type {
+        match self {
+            Type::Nullable(ty) => *ty.to_owned(),
+            Type::Return(ty) => ty.non_null(),
+            _ => self.to_owned(),
+        }
+    }
+    /// The type of a value that is either of this type or an error, `T!?`
+    pub fn errorable(self) -> Type {
+        if matches!(self, Type::Errorable(_)) || self == Type::any() {
+            self
+        } else {
+            Type::Errorable(Box::new(self))
+        }
+    }
+    /// This type without errors, eg T for `T!?`
+    pub fn non_error(&self) -> Type {
+        match self {
+            Type::Errorable(ty) => *ty.to_owned(),
+            Type::Return(ty) => ty.non_error(),
+            _ => self.to_owned(),
+        }
+    }
+    /// The type of a value that is of either of two types that differ only in whether they can be null,
+    /// eg `T?` for T and null
+    pub fn or_null(&self, other: &Type) -> Option<Type> {
+        if self == other {
+            return Some(self.to_owned());
+        }
+        let inner = if *self == Type::null() {
+            other.non_null()
+        } else if *other == Type::null() || self.non_null() == other.non_null() {
+            self.non_null()
+        } else {
+            return None;
+        };
+        Some(inner.nullable())
+    }
+    pub fn is_num(&self) -> bool {
+        if let Type::Instance { name, .. } = self {
+            [
+                "i8", "i16", "i32", "i64", "i128", "isize", "ibig", "u8", "u16", "u32", "u64",
+                "u128", "usize", "ubig", "f16", "f32", "f64",
+            ]
+            .contains(&&**name)
+        } else {
+            false
+        }
+    }
+    pub fn is_int(&self) -> bool {
+        self.is_num() && !matches!(self, Type::Instance { name, .. } if name.starts_with('f'))
+    }
+    pub fn generic(name: &str, type_args: Vec<Type>) -> Self {
+        Type::Instance {
+            name: name.to_string(),
+            type_args,
+            inst_attrs: Default::default(),
+            implementation: None,
+        }
+    }
+    pub fn sequence(item_type: Type, is_vec: bool) -> Self {
+        Type::generic(if is_vec { "vec" } else { "array" }, vec![item_type])
+    }
+    /// The type of a reference (`ref<T>`) or pointer (`ptr<T>`) to a variable of type `type_`
+    pub fn reference(type_: Type, is_pointer: bool) -> Self {
+        Type::generic(if is_pointer { "ptr" } else { "ref" }, vec![type_])
+    }
+    /// The type of the variable a reference or pointer refers to, and whether it is a pointer,
+    /// or None if the type is of neither
+    pub fn referenced(&self) -> Option<(Type, bool)> {
+        match self {
+            Type::Instance {
+                name, type_args, ..
+            } if name == "ref" || name == "ptr" => Some((
+                type_args.first().cloned().unwrap_or_else(Type::any),
+                name == "ptr",
+            )),
+            _ => None,
+        }
+    }
+    /// Whether the type is that of an argument collecting the leftover positional arguments (`#varg<T>`)
+    pub fn is_varg(&self) -> bool {
+        matches!(self, Type::Instance { name, .. } if name == "#varg")
+    }
+    /// Whether the type is that of an argument collecting the leftover keyword arguments (`#vkwarg<str, T>`)
+    pub fn is_vkwarg(&self) -> bool {
+        matches!(self, Type::Instance { name, .. } if name == "#vkwarg")
+    }
+    /// The type of each of the values a variable argument collects
+    pub fn variadic_item_type(&self) -> Type {
+        if let Type::Instance { type_args, .. } = self {
+            type_args.last().cloned().unwrap_or_else(Type::any)
+        } else {
+            Type::any()
+        }
+    }
+    /// The type of the instances of a class or struct, or of the variants of an enum.
+    /// Attributes not found in the instance are looked up in the definition
+    pub fn instance_type(&self) -> Type {
+        match self {
+            Type::Enum { name, .. } => Type::Instance {
+                name: name.to_owned(),
+                type_args: vec![],
+                inst_attrs: Default::default(),
+                implementation: Some(Box::new(self.to_owned())),
+            },
+            Type::Definition { name, .. } => Type::Instance {
+                name: name.to_owned(),
+                type_args: vec![],
+                inst_attrs: self.all_inst_attrs(),
+                implementation: Some(Box::new(self.to_owned())),
+            },
+            Type::Return(ty) => ty.instance_type(),
+            _ => Type::any(),
+        }
+    }
+    /// The type of the instances of a generic class or struct, with its generic parameters given the bound types.
+    /// Unbound parameters are given `_any`
+    pub fn generic_instance_type(&self, bindings: &HashMap<String, Type>) -> Type {
+        let mut instance_type = self.instance_type();
+        if let (Type::Definition { generics, .. }, Type::Instance { type_args, .. }) =
+            (self, &mut instance_type)
+        {
+            *type_args = generics
+                .iter()
+                .map(|generic| {
+                    bindings
+                        .get(&generic.name)
+                        .cloned()
+                        .unwrap_or_else(Type::any)
+                })
+                .collect();
+        }
+        instance_type
+    }
+    /// The method resolution order of a class: the class, then its parents and their ancestors.
+    /// A class always comes before its parents, and parents keep the order they are inherited in.
+    /// None if there is no order that does both
+    pub fn mro(&self) -> Option<Vec<Type>> {
+        let parents = if let Type::Definition { parents, .. } = self {
+            parents
+        } else {
+            return Some(vec![]);
+        };
+        let mut sequences = parents
+            .iter()
+            .map(|parent| parent.mro())
+            .collect::<Option<Vec<_>>>()?;
+        sequences.push(parents.to_owned());
+        let mut mro = vec![self.to_owned()];
+        loop {
+            sequences.retain(|sequence| !sequence.is_empty());
+            if sequences.is_empty() {
+                return Some(mro);
+            }
+            // the next class is the first one that no other class has to come before
+            let next = sequences
+                .iter()
+                .map(|sequence| &sequence[0])
+                .find(|head| {
+                    sequences
+                        .iter()
+                        .all(|sequence| !sequence[1..].contains(head))
+                })?
+                .to_owned();
+            for sequence in sequences.iter_mut() {
+                if sequence[0] == next {
+                    sequence.remove(0);
+                }
+            }
+            mro.push(next);
+        }
+    }
+    /// The instance attributes of a class, including those it inherits
+    pub fn all_inst_attrs(&self) -> HashMap<String, Element> {
+        let mut attrs = HashMap::new();
+        for class in self.mro().unwrap_or_default().iter().rev() {
+            if let Type::Definition { inst_attrs, .. } = class {
+                attrs.extend(inst_attrs.to_owned());
+            }
+        }
+        attrs
+    }
+    /// The type of an attribute given to instances in the `_init` of a class or of one it inherits from
+    pub fn init_attr_type(&self, name: &str) -> Option<Type> {
+        let definition = match self {
+            Type::Instance {
+                implementation: Some(definition),
+                ..
+            } => &**definition,
+            _ => self,
+        };
+        definition
+            .mro()
+            .unwrap_or_default()
+            .into_iter()
+            .find_map(|class| match class {
+                Type::Definition { init_attrs, .. } => init_attrs.get(name).cloned(),
+                _ => None,
+            })
+    }
+    /// The name of the class that declares the attribute `name` of a class or of its instances
+    pub fn attr_owner(&self, name: &str) -> Option<String> {
+        let definition = match self {
+            Type::Instance {
+                implementation: Some(definition),
+                ..
+            } => &**definition,
+            _ => self,
+        };
+        definition
+            .mro()
+            .unwrap_or_default()
+            .into_iter()
+            .find_map(|class| match class {
+                Type::Definition {
+                    name: class_name,
+                    class_attrs,
+                    inst_attrs,
+                    ..
+                } if class_attrs.contains_key(name) || inst_attrs.contains_key(name) => {
+                    Some(class_name)
+                }
+                _ => None,
+            })
+    }
+    /// The flags the attribute `name` of a class or of its instances was declared with, by the class that declares it
+    pub fn attr_flags(&self, name: &str) -> Vec<Flag> {
+        let definition = match self {
+            Type::Instance {
+                implementation: Some(definition),
+                ..
+            } => &**definition,
+            _ => self,
+        };
+        definition
+            .mro()
+            .unwrap_or_default()
+            .into_iter()
+            .find_map(|class| match class {
+                Type::Definition {
+                    class_attrs,
+                    inst_attrs,
+                    attr_flags,
+                    ..
+                } if class_attrs.contains_key(name) || inst_attrs.contains_key(name) => {
+                    Some(attr_flags.get(name).cloned().unwrap_or_default())
+                }
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+    /// Whether the type is that of the instances of a class named `name`, or of a class inheriting from it
+    pub fn inherits_from(&self, name: &str) -> bool {
+        if let Type::Instance {
+            implementation: Some(definition),
+            ..
+        } = self
+        {
+            definition
+                .mro()
+                .unwrap_or_default()
+                .iter()
+                .any(|class| matches!(class, Type::Definition { name: class_name, .. } if class_name == name))
+        } else {
+            false
+        }
+    }
+    /// The fields of the instances of a struct, or None if the type is not that of a struct's instances
+    pub fn fields(&self) -> Option<&Vec<Argument>> {
+        match self {
+            Type::Instance {
+                implementation: Some(definition),
+                ..
+            } => match &**definition {
+                Type::Definition {
+                    is_struct: true,
+                    fields,
+                    ..
+                } => Some(fields),
+                _ => None,
+            },
+            Type::Return(ty) => ty.fields(),
+            _ => None,
+        }
+    }
+    /// Whether the type is a class, struct or enum, or the type of its instances
+    pub fn is_user_defined(&self) -> bool {
+        matches!(
+            self,
+            Type::Definition { .. }
+                | Type::Enum { .. }
+                | Type::Instance {
+                    implementation: Some(_),
+                    ..
+                }
+        )
+    }
+    pub fn proc(is_fn: bool, args: &[Argument], return_type: Type) -> Self {
+        Type::Proc {
+            is_fn,
+            params: args.iter().map(Param::from).collect(),
+            return_type: Box::new(return_type),
+        }
+    }
+    /// Turns the names of generic parameters in a type annotation into the parameters themselves
+    pub fn with_generics(self, generics: &[Argument]) -> Type {
+        match self {
+            Type::Instance {
+                name, type_args, ..
+            } if type_args.is_empty() && generics.iter().any(|generic| generic.name == name) => {
+                Type::Generic(name)
+            }
+            Type::Instance {
+                name,
+                type_args,
+                inst_attrs,
+                implementation,
+            } => Type::Instance {
+                name,
+                type_args: type_args
+                    .into_iter()
+                    .map(|type_arg| type_arg.with_generics(generics))
+                    .collect(),
+                inst_attrs,
+                implementation,
+            },
+            _ => self,
+        }
+    }
+    /// The types that the generic parameters of a class or struct are given in this type of its instances
+    pub fn generic_bindings(&self) -> HashMap<String, Type> {
+        match self {
+            Type::Instance {
+                type_args,
+                implementation: Some(definition),
+                ..
+            } => match &**definition {
+                Type::Definition { generics, .. } => generics
+                    .iter()
+                    .zip(type_args)
+                    .map(|(generic, type_arg)| (generic.name.to_owned(), type_arg.to_owned()))
+                    .collect(),
+                _ => HashMap::new(),
+            },
+            Type::Return(ty) => ty.generic_bindings(),
+            _ => HashMap::new(),
+        }
+    }
+    /// Whether a procedure with these parameters can be called with arguments of these types without converting them
+    pub fn params_accept(
+        params: &[Param],
+        arg_types: &[Type],
+        kwarg_types: &HashMap<String, Type>,
+    ) -> bool {
+        let mut arg_types = arg_types.iter();
+        let mut kwarg_names = kwarg_types.keys().collect::<Vec<_>>();
+        let mut vkwarg = None;
+        for param in params {
+            if param.type_.is_varg() {
+                let item_type = param.type_.variadic_item_type();
+                if !arg_types
+                    .by_ref()
+                    .all(|arg_type| item_type.accepts(arg_type))
+                {
+                    return false;
+                }
+            } else if param.type_.is_vkwarg() {
+                vkwarg = Some(param);
+            } else if let Some(arg_type) = arg_types.next() {
+                if kwarg_types.contains_key(&param.name) || !param.type_.accepts(arg_type) {
+                    return false;
+                }
+            } else if let Some(kwarg_type) = kwarg_types.get(&param.name) {
+                kwarg_names.retain(|name| **name != param.name);
+                if !param.type_.accepts(kwarg_type) {
+                    return false;
+                }
+            } else if !param.has_default {
+                return false;
+            }
+        }
+        arg_types.next().is_none()
+            && match vkwarg {
+                Some(vkwarg) => kwarg_names.iter().all(|name| {
+                    vkwarg
+                        .type_
+                        .variadic_item_type()
+                        .accepts(&kwarg_types[*name])
+                }),
+                None => kwarg_names.is_empty(),
+            }
+    }
+    /// Binds the generic parameters in the parameters of a procedure to the types of the arguments it is called with
+    pub fn infer_generic_args(
+        params: &[Param],
+        arg_types: &[Type],
+        kwarg_types: &HashMap<String, Type>,
+    ) -> HashMap<String, Type> {
+        let mut bindings = HashMap::new();
+        let mut arg_types = arg_types.iter();
+        for param in params {
+            if param.type_.is_varg() {
+                for arg_type in arg_types.by_ref() {
+                    param
+                        .type_
+                        .variadic_item_type()
+                        .infer_generics(arg_type, &mut bindings);
+                }
+            } else if param.type_.is_vkwarg() {
+                continue;
+            } else if let Some(arg_type) = arg_types.next() {
+                param.type_.infer_generics(arg_type, &mut bindings);
+            } else if let Some(kwarg_type) = kwarg_types.get(&param.name) {
+                param.type_.infer_generics(kwarg_type, &mut bindings);
+            }
+        }
+        bindings
+    }
+    /// Binds the generic parameters in this type to the parts of the given type in their place.
+    /// Parameters that are already bound keep their type
+    pub fn infer_generics(&self, given: &Type, bindings: &mut HashMap<String, Type>) {
+        match (self, given) {
+            (Type::Generic(name), _) => {
+                bindings
+                    .entry(name.to_owned())
+                    .or_insert_with(|| given.to_owned());
+            }
+            (
+                Type::Instance {
+                    name, type_args, ..
+                },
+                Type::Instance {
+                    name: name2,
+                    type_args: type_args2,
+                    ..
+                },
+            ) if name == name2 => {
+                for (type_arg, given) in type_args.iter().zip(type_args2) {
+                    type_arg.infer_generics(given, bindings);
+                }
+            }
+            (
+                Type::Proc {
+                    params,
+                    return_type,
+                    ..
+                },
+                Type::Proc {
+                    params: params2,
+                    return_type: return_type2,
+                    ..
+                },
+            ) => {
+                for (param, given) in params.iter().zip(params2) {
+                    param.type_.infer_generics(&given.type_, bindings);
+                }
+                return_type.infer_generics(return_type2, bindings);
+            }
+            (Type::Nullable(ty), _) => ty.infer_generics(&given.non_null(), bindings),
+            (Type::Errorable(ty), _) => ty.infer_generics(&given.non_error(), bindings),
+            _ => (),
+        }
+    }
+    /// Replaces the generic parameters in this type with the types bound to them.
+    /// Parameters that are not bound are replaced with `unbound`, or left as they are
+    pub fn substitute(&self, bindings: &HashMap<String, Type>, unbound: Option<&Type>) -> Type {
+        match self {
+            Type::Generic(name) => bindings
+                .get(name)
+                .or(unbound)
+                .cloned()
+                .unwrap_or_else(|| self.to_owned()),
+            Type::Instance {
+                name,
+                type_args,
+                inst_attrs,
+                implementation,
+            } => Type::Instance {
+                name: name.to_owned(),
+                type_args: type_args
+                    .iter()
+                    .map(|type_arg| type_arg.substitute(bindings, unbound))
+                    .collect(),
+                inst_attrs: inst_attrs.to_owned(),
+                implementation: implementation.to_owned(),
+            },
+            Type::Proc {
+                is_fn,
+                params,
+                return_type,
+            } => Type::Proc {
+                is_fn: *is_fn,
+                params: params
+                    .iter()
+                    .map(|param| param.substitute(bindings, unbound))
+                    .collect(),
+                return_type: Box::new(return_type.substitute(bindings, unbound)),
+            },
+            Type::Nullable(ty) => Type::Nullable(Box::new(ty.substitute(bindings, unbound))),
+            Type::Errorable(ty) => Type::Errorable(Box::new(ty.substitute(bindings, unbound))),
+            Type::Return(ty) => Type::Return(Box::new(ty.substitute(bindings, unbound))),
+            _ => self.to_owned(),
+        }
+    }
+    /// Whether a value of type `other` can be used where this type is expected.
+    /// `_any` accepts and is accepted by every type
+    pub fn accepts(&self, other: &Type) -> bool {
+        match (self, other) {
+            _ if self == other || *self == Type::any() || *other == Type::any() => true,
+            // `#no_inherited<T>` only accepts T itself
+            (
+                Type::Instance {
+                    name, type_args, ..
+                },
+                Type::Instance { name: name2, .. },
+            ) if name == "#no_inherited" => type_args
+                .first()
+                .is_some_and(|type_| type_.accepts(other) && type_.to_string() == *name2),
+            // a type without type arguments accepts the type with any type arguments
+            (
+                Type::Instance {
+                    name: name1,
+                    type_args: type_args1,
+                    ..
+                },
+                Type::Instance {
+                    name: name2,
+                    type_args: type_args2,
+                    ..
+                },
+            ) if name1 == name2 => {
+                type_args1.is_empty()
+                    || (type_args1.len() == type_args2.len()
+                        && type_args1
+                            .iter()
+                            .zip(type_args2)
+                            .all(|(t1, t2)| t1.accepts(t2)))
+            }
+            (Type::Instance { name, .. }, _) => other.inherits_from(name),
+            // `T?` accepts null, T, and `U?` if T accepts U
+            (Type::Nullable(ty), Type::Nullable(other)) => ty.accepts(other),
+            (Type::Nullable(ty), _) => *other == Type::null() || ty.accepts(other),
+            // `T!?` accepts errors, T, and `U!?` if T accepts U
+            (Type::Errorable(ty), Type::Errorable(other)) => ty.accepts(other),
+            (Type::Errorable(ty), _) => *other == Type::from_name("err") || ty.accepts(other),
+            (Type::Return(ty), _) => ty.accepts(other),
+            (_, Type::Return(ty)) => self.accepts(ty),
+            // a procedure type also accepts functions, which are procedures that change nothing
+            (
+                Type::Proc {
+                    is_fn,
+                    params,
+                    return_type,
+                },
+                Type::Proc {
+                    is_fn: is_fn2,
+                    params: params2,
+                    return_type: return_type2,
+                },
+            ) => {
+                (!is_fn || *is_fn2)
+                    && params.len() == params2.len()
+                    && params
+                        .iter()
+                        .zip(params2)
+                        .all(|(param, param2)| param2.type_.accepts(&param.type_))
+                    && return_type.accepts(return_type2)
+            }
+            _ => false,
+        }
+    }
     pub fn as_element(&self) -> Element {
         match self {
             Type::Instance { name, .. } => Element::Variable {
@@ -73,8 +747,95 @@ impl Type {
                 raw: self.to_string(),
                 parent: Box::new(Element::NullElement),
             },
-            Type::Definition { .. } => todo!(),
-            Type::Return(ty) => ty.as_element(),
+            Type::Definition {
+                name,
+                is_struct,
+                is_mixin,
+                generics,
+                fields,
+                parents,
+                class_attrs,
+                inst_attrs,
+                ..
+            } => Element::Class {
+                position: Default::default(),
+                raw: self.to_string(),
+                name: Some(name.to_owned()),
+                is_struct: *is_struct,
+                is_mixin: *is_mixin,
+                generics: generics.to_owned(),
+                class_attrs: class_attrs.to_owned(),
+                inst_attrs: inst_attrs.to_owned(),
+                content: parents
+                    .iter()
+                    .map(|parent| Element::Inherit {
+                        position: Default::default(),
+                        raw: format!("..{}", parent),
+                        parent: Box::new(parent.as_element()),
+                    })
+                    .collect(),
+                args: if *is_struct {
+                    Some(fields.to_owned())
+                } else {
+                    None
+                },
+            },
+            Type::Enum { name, variants } => Element::Enum {
+                position: Default::default(),
+                raw: self.to_string(),
+                name: Some(name.to_owned()),
+                variants: variants.to_owned(),
+            },
+            Type::Overload(procs) => Element::Overload {
+                position: Default::default(),
+                raw: self.to_string(),
+                procs: procs.to_owned(),
+            },
+            Type::Generic(name) => Element::Variable {
+                position: Default::default(),
+                name: name.to_owned(),
+                raw: self.to_string(),
+                parent: Box::new(Element::NullElement),
+            },
+            Type::Proc { is_fn, .. } => Element::Variable {
+                position: Default::default(),
+                name: if *is_fn { "fn" } else { "proc" }.to_string(),
+                raw: self.to_string(),
+                parent: Box::new(Element::NullElement),
+            },
+            Type::Nullable(ty) | Type::Errorable(ty) | Type::Return(ty) => ty.as_element(),
+        }
+    }
+    /// The type of the items of a collection, or None if the type is not a collection.
+    /// The items of a dict are its keys
+    pub fn item_type(&self) -> Option<Type> {
+        match self {
+            Type::Instance { name, .. } if name == "str" => Some(Type::from_name("str")),
+            Type::Instance { name, .. } if name == "_any" => Some(Type::any()),
+            Type::Instance {
+                name, type_args, ..
+            } if ["array", "vec", "set", "fset", "dict"].contains(&&**name) => {
+                Some(type_args.first().cloned().unwrap_or_else(Type::any))
+            }
+            Type::Instance {
+                name, type_args, ..
+            } if name == "tuple" => {
+                Some(if type_args.windows(2).all(|types| types[0] == types[1]) {
+                    type_args.first().cloned().unwrap_or_else(Type::any)
+                } else {
+                    Type::any()
+                })
+            }
+            Type::Return(ty) => ty.item_type(),
+            _ => None,
+        }
+    }
+    pub fn iter_type(&self) -> Option<Type> {
+        match self {
+            Type::Return(ty) => ty.iter_type(),
+            _ if self.item_type().is_some() => self.item_type(),
+            _ if self.get_attrs().contains_key("_iter") => Some(Type::any()),
+            _ => None,
         }
     }
     pub fn get_attrs(&self) -> HashMap<String, Element> {
@@ -95,20 +856,29 @@ impl Type {
                 }
                 attrs
             }
-            Type::Definition {
-                class_attrs,
-                inst_attrs,
-                ..
-            } => {
+            Type::Definition { .. } => {
+                // attributes of classes earlier in the method resolution order take precedence
                 let mut attrs = HashMap::new();
-                for (name, element) in class_attrs.iter() {
-                    attrs.insert(name.to_owned(), element.to_owned());
-                }
-                for (name, element) in inst_attrs.iter() {
-                    attrs.insert(name.to_owned(), element.to_owned());
+                for class in self.mro().unwrap_or_default().iter().rev() {
+                    if let Type::Definition {
+                        class_attrs,
+                        inst_attrs,
+                        ..
+                    } = class
+                    {
+                        attrs.extend(class_attrs.to_owned());
+                        attrs.extend(inst_attrs.to_owned());
+                    }
                 }
                 attrs
             }
+            // a nullable value has no attributes until it is known not to be null
+            Type::Enum { .. }
+            | Type::Generic(_)
+            | Type::Overload(_)
+            | Type::Proc { .. }
+            | Type::Nullable(_)
+            | Type::Errorable(_) => HashMap::new(),
             Type::Return(ty) => ty.get_attrs(), // TODO get class from type, maybe?
         }
     }
@@ -3,26 +3,32 @@ mod concat;
 mod div;
 mod eq;
 mod gt;
+pub mod iter;
 pub mod logic;
 mod lt;
 mod modulo;
 mod mul;
-mod pow;
+pub mod pow;
+pub mod seq;
 mod sub;
 mod typecast;
 mod unary;
 pub mod utils;
 
 use crate::objects::element::Argument;
+use crate::objects::interpreter_data::{Environment, Slot};
 use crate::objects::token::OprType;
 use crate::objects::typeobj::Type;
+use crate::objects::value::div::Rounding;
 use crate::objects::value::utils::OprError;
 use crate::{Element, ZyxtError};
 use enum_as_inner::EnumAsInner;
 use half::f16;
+use indexmap::{IndexMap, IndexSet};
 use num::{BigInt, BigUint};
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 
 #[derive(Clone, PartialEq, EnumAsInner)]
 pub enum Value {
@@ -46,25 +52,51 @@ pub enum Value {
     Str(String),
     Bool(bool),
     Type(Type),
+    Array {
+        is_vec: bool,
+        type_: Type, // type of the items
+        content: Vec<Value>,
+    },
+    Tuple(Vec<Value>),
+    Set {
+        is_frozen: bool,
+        type_: Type, // type of the items
+        content: IndexSet<Value>,
+    },
+    Dict {
+        key_type: Type,
+        value_type: Type,
+        content: IndexMap<Value, Value>,
+    },
     Proc {
         is_fn: bool,
         args: Vec<Argument>,
         return_type: Type,
         content: Vec<Element>,
+        label: Option<String>,
+        environment: Environment,
     },
+    Overload(Vec<Value>), // procs of the same name with different parameters
     ClassInstance {
         type_: Type,
         attrs: HashMap<String, Value>,
     },
+    EnumVariant {
+        type_: Type,
+        variant: String,
+        value: Box<Value>, // the value of the variant, the struct instance of a struct variant, or null
+    },
+    Error(ZyxtError), // an error that was caught, or returned by a procedure returning `T!?`
+    Ref {
+        is_pointer: bool, // pointers can also change the variable they point to
+        type_: Type,      // the type of the variable
+        slot: Slot,
+    },
     Null,
-    Return(Box<Value>),
 }
 
 impl Debug for Value {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if let Value::Return(v) = self {
-            return Debug::fmt(&v, f);
-        }
         write!(
             f,
             "{}",
@@ -87,12 +119,17 @@ impl Debug for Value {
                 Value::F32(v) => format!("{}@f32", v),
                 Value::F64(v) => format!("{}@f64", v),
                 Value::Str(v) => format!("\"{}\"", v),
+                Value::Array { .. } | Value::Tuple(_) | Value::Set { .. } | Value::Dict { .. } =>
+                    self.fmt_collection(|v| format!("{:?}", v)),
                 Value::Bool(_)
                 | Value::Type(_)
                 | Value::ClassInstance { .. }
+                | Value::EnumVariant { .. }
                 | Value::Proc { .. }
+                | Value::Overload(_)
+                | Value::Error(_)
+                | Value::Ref { .. }
                 | Value::Null => self.to_string(),
-                Value::Return(_) => unreachable!(),
             }
         )
     }
@@ -122,7 +159,34 @@ impl Display for Value {
                 Value::F64(v) => v.to_string(),
                 Value::Str(v) => v.to_owned(),
                 Value::Bool(v) => v.to_string(),
+                Value::Array { .. } | Value::Tuple(_) | Value::Set { .. } | Value::Dict { .. } =>
+                    self.fmt_collection(|v| {
+                        if let Value::Str(v) = v {
+                            format!("\"{}\"", v)
+                        } else {
+                            v.to_string()
+                        }
+                    }),
+                Value::ClassInstance { type_, attrs } if type_.fields().is_some() => format!(
+                    "{}({})",
+                    type_,
+                    type_
+                        .fields()
+                        .unwrap()
+                        .iter()
+                        .map(|field| match &attrs[&field.name] {
+                            Value::Str(v) => format!("{}: \"{}\"", field.name, v),
+                            v => format!("{}: {}", field.name, v),
+                        })
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
                 Value::Type(v) | Value::ClassInstance { type_: v, .. } => format!("<{}>", v),
+                // struct variants are printed as their struct, which is named after the variant
+                Value::EnumVariant { value, .. }
+                    if matches!(**value, Value::ClassInstance { .. }) =>
+                    value.to_string(),
+                Value::EnumVariant { type_, variant, .. } => format!("{}.{}", type_, variant),
                 Value::Proc {
                     is_fn,
                     args,
@@ -137,14 +201,97 @@ impl Display for Value {
                         .join(","),
                     return_type
                 ),
+                Value::Overload(procs) => procs
+                    .iter()
+                    .map(|proc| proc.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                Value::Error(e) => format!("err({}: {})", e.code, e.message),
+                Value::Ref {
+                    is_pointer, slot, ..
+                } => format!("{}{}", if *is_pointer { "&>" } else { "&" }, slot.name),
                 Value::Null => "null".to_string(),
-                Value::Return(v) => v.to_string(),
             }
         )
     }
 }
 
+// `Eq` is needed to store values in sets and as dictionary keys. NaN floats are the one exception
+// to reflexivity, so NaN keys can't be found again, like in most other languages
+impl Eq for Value {}
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        // 0.0 == -0.0, so both have to hash the same
+        macro_rules! hash_float {
+            ($v: ident) => {
+                if *$v == 0.0 { 0 } else { $v.to_bits() }.hash(state)
+            };
+        }
+        match self {
+            Value::I8(v) => v.hash(state),
+            Value::I16(v) => v.hash(state),
+            Value::I32(v) => v.hash(state),
+            Value::I64(v) => v.hash(state),
+            Value::I128(v) => v.hash(state),
+            Value::Isize(v) => v.hash(state),
+            Value::Ibig(v) => v.hash(state),
+            Value::U8(v) => v.hash(state),
+            Value::U16(v) => v.hash(state),
+            Value::U32(v) => v.hash(state),
+            Value::U64(v) => v.hash(state),
+            Value::U128(v) => v.hash(state),
+            Value::Usize(v) => v.hash(state),
+            Value::Ubig(v) => v.hash(state),
+            Value::F16(v) => if *v == f16::ZERO { 0 } else { v.to_bits() }.hash(state),
+            Value::F32(v) => hash_float!(v),
+            Value::F64(v) => hash_float!(v),
+            Value::Str(v) => v.hash(state),
+            Value::Bool(v) => v.hash(state),
+            Value::Array { content, .. } | Value::Tuple(content) => content.hash(state),
+            // sets and dicts are equal regardless of order, so only their size is hashed
+            Value::Set { content, .. } => content.len().hash(state),
+            Value::Dict { content, .. } => content.len().hash(state),
+            Value::Type(_)
+            | Value::Proc { .. }
+            | Value::Overload(_)
+            | Value::ClassInstance { .. }
+            | Value::EnumVariant { .. }
+            | Value::Error(_)
+            | Value::Ref { .. } => self.to_string().hash(state),
+            Value::Null => (),
+        }
+    }
+}
+
 impl Value {
+    /// Formats a collection like its literal, formatting each item with `fmt_item`
+    fn fmt_collection(&self, fmt_item: impl Fn(&Value) -> String) -> String {
+        let (prefix, items): (&str, Vec<String>) = match self {
+            Value::Array {
+                is_vec, content, ..
+            } => (
+                if *is_vec { "vec" } else { "" },
+                content.iter().map(&fmt_item).collect(),
+            ),
+            Value::Tuple(content) => ("tuple", content.iter().map(&fmt_item).collect()),
+            Value::Set {
+                is_frozen, content, ..
+            } => (
+                if *is_frozen { "fset" } else { "set" },
+                content.iter().map(&fmt_item).collect(),
+            ),
+            Value::Dict { content, .. } => (
+                "dict",
+                content
+                    .iter()
+                    .map(|(k, v)| format!("{}: {}", fmt_item(k), fmt_item(v)))
+                    .collect(),
+            ),
+            _ => unreachable!(),
+        };
+        format!("{}[{}]", prefix, items.join(", "))
+    }
     pub fn call(&self, args: Vec<Value>) -> Result<Value, OprError> {
         if args.len() == 1 {
             macro_rules! mult {
@@ -170,9 +317,6 @@ impl Value {
                 Value::F32(_) => mult!(),
                 Value::F64(_) => mult!(),
                 Value::Proc { .. } => panic!(),
-                Value::Return(v) => v.call(args),
-                Value::Type(_v) => todo!(),
-                Value::ClassInstance { type_: _, .. } => todo!(),
                 _ => Err(OprError::NoImplForOpr),
             }
         } else {
@@ -180,26 +324,34 @@ impl Value {
         }
     }
     pub fn un_opr(&self, type_: &OprType) -> Result<Value, OprError> {
-        if let Value::Return(v) = self {
-            return v.un_opr(type_);
-        }
         match type_ {
             OprType::MinusSign => unary::un_minus(self),
             OprType::PlusSign => unary::un_plus(self),
             OprType::Not => unary::un_not(self),
+            OprType::Increment => add::add(self, Value::U8(1)),
+            OprType::Decrement => sub::sub(self, Value::U8(1)),
             _ => Err(OprError::NoImplForOpr),
         }
     }
     pub fn bin_opr(&self, type_: &OprType, other: Value) -> Result<Value, OprError> {
-        if let Value::Return(v) = self {
-            return v.bin_opr(type_, other);
-        }
         match type_ {
             OprType::Plus => add::add(self, other),
             OprType::Minus => sub::sub(self, other),
             OprType::AstMult | OprType::DotMult | OprType::CrossMult => mul::mul(self, other),
             OprType::Div | OprType::FractDiv => div::div(self, other),
+            OprType::FloorDiv | OprType::FloorfractDiv => {
+                div::rounded_div(self, other, Rounding::Floor)
+            }
+            OprType::CeilDiv | OprType::CeilfractDiv => {
+                div::rounded_div(self, other, Rounding::Ceil)
+            }
+            OprType::RoundDiv | OprType::RoundfractDiv => {
+                div::rounded_div(self, other, Rounding::Round)
+            }
             OprType::Modulo => modulo::modulo(self, other),
+            OprType::Power => pow::pow(self, other),
+            OprType::Root => pow::root(self, other),
+            OprType::Logarithm => pow::log(self, other),
 
             OprType::Eq => eq::eq(self, other),
             OprType::Noteq => eq::noteq(self, other),
@@ -210,8 +362,8 @@ impl Value {
             OprType::Iseq => eq::iseq(self, other),
             OprType::Isnteq => eq::isnteq(self, other),
 
-            OprType::And => unreachable!(),
-            OprType::Or => unreachable!(),
+            OprType::And => logic::and(self, &other),
+            OprType::Or => logic::or(self, &other),
             OprType::Xor => logic::xor(self, &other),
 
             OprType::Concat => concat::concat(self, other),
@@ -244,7 +396,9 @@ impl Value {
     }
     pub fn default(type_: Type) -> Result<Self, ZyxtError> {
         match type_.to_owned() {
-            Type::Instance { name, .. } => Ok(match &*name {
+            Type::Instance {
+                name, type_args, ..
+            } => Ok(match &*name {
                 "i8" => Value::I8(0),
                 "i16" => Value::I16(0),
                 "i32" => Value::I32(0),
@@ -259,12 +413,34 @@ impl Value {
                 "u128" => Value::U128(0),
                 "usize" => Value::Usize(0),
                 "ubig" => Value::Ubig(0u32.into()),
+                "f16" => Value::F16(f16::ZERO),
                 "f32" => Value::F32(0.0),
                 "f64" => Value::F64(0.0),
                 "str" => Value::Str("".to_string()),
                 "bool" => Value::Bool(false),
                 "_null" | "_any" => Value::Null, // TODO move _any somewhere else
                 "type" => Value::Type(Type::null()),
+                "array" | "vec" => Value::Array {
+                    is_vec: name == "vec",
+                    type_: type_args.first().cloned().unwrap_or_else(Type::any),
+                    content: vec![],
+                },
+                "tuple" => Value::Tuple(
+                    type_args
+                        .into_iter()
+                        .map(Value::default)
+                        .collect::<Result<Vec<_>, _>>()?,
+                ),
+                "set" | "fset" => Value::Set {
+                    is_frozen: name == "fset",
+                    type_: type_args.first().cloned().unwrap_or_else(Type::any),
+                    content: IndexSet::new(),
+                },
+                "dict" => Value::Dict {
+                    key_type: type_args.first().cloned().unwrap_or_else(Type::any),
+                    value_type: type_args.get(1).cloned().unwrap_or_else(Type::any),
+                    content: IndexMap::new(),
+                },
                 _ => panic!("{:#?}", type_),
             }),
             _ => panic!(),
@@ -292,6 +468,7 @@ impl Value {
                 "f64" => Value::F64(content.parse::<f64>().unwrap()),
                 "str" => Value::Str(content),
                 "bool" => Value::Bool(&*content == "true"),
+                "_null" => Value::Null,
                 _ => panic!(),
             },
             _ => panic!(),
@@ -319,17 +496,36 @@ impl Value {
             Value::Str(..) => Type::from_name("str"),
             Value::Bool(..) => Type::from_name("bool"),
             Value::Type(..) => Type::from_name("type"),
+            Value::Array { is_vec, type_, .. } => Type::sequence(type_.to_owned(), *is_vec),
+            Value::Tuple(content) => {
+                Type::generic("tuple", content.iter().map(|v| v.get_type_obj()).collect())
+            }
+            Value::Set {
+                is_frozen, type_, ..
+            } => Type::generic(
+                if *is_frozen { "fset" } else { "set" },
+                vec![type_.to_owned()],
+            ),
+            Value::Dict {
+                key_type,
+                value_type,
+                ..
+            } => Type::generic("dict", vec![key_type.to_owned(), value_type.to_owned()]),
             Value::Proc {
-                is_fn, return_type, ..
-            } => Type::Instance {
-                name: if *is_fn { "fn" } else { "proc" }.to_string(),
-                type_args: vec![Type::null(), return_type.to_owned()],
-                inst_attrs: Default::default(),
-                implementation: None,
-            }, // TODO angle bracket thingy when it is implemented
-            Value::ClassInstance { type_, .. } => type_.to_owned(),
+                is_fn,
+                args,
+                return_type,
+                ..
+            } => Type::proc(*is_fn, args, return_type.to_owned()),
+            Value::Overload(procs) => Type::Overload(procs.iter().map(Value::as_element).collect()),
+            Value::ClassInstance { type_, .. } | Value::EnumVariant { type_, .. } => {
+                type_.to_owned()
+            }
+            Value::Error(_) => Type::from_name("err"),
+            Value::Ref {
+                is_pointer, type_, ..
+            } => Type::reference(type_.to_owned(), *is_pointer),
             Value::Null => Type::null(),
-            Value::Return(v) => v.get_type_obj(),
         }
     }
     pub fn get_type(&self) -> Value {
@@ -367,26 +563,69 @@ impl Value {
             Value::Str(v) => to_literal!(v),
             Value::Bool(v) => to_literal!(v),
             Value::Type(v) => to_literal!(v),
+            Value::Array {
+                is_vec, content, ..
+            } => Element::Array {
+                position: Default::default(),
+                raw: self.to_string(),
+                is_vec: *is_vec,
+                content: content.iter().map(|v| v.as_element()).collect(),
+            },
+            Value::Tuple(content) => Element::Tuple {
+                position: Default::default(),
+                raw: self.to_string(),
+                content: content.iter().map(|v| v.as_element()).collect(),
+            },
+            Value::Set {
+                is_frozen, content, ..
+            } => Element::SetLiteral {
+                position: Default::default(),
+                raw: self.to_string(),
+                is_frozen: *is_frozen,
+                content: content.iter().map(|v| v.as_element()).collect(),
+            },
+            Value::Dict { content, .. } => Element::Dict {
+                position: Default::default(),
+                raw: self.to_string(),
+                content: content
+                    .iter()
+                    .map(|(k, v)| (k.as_element(), v.as_element()))
+                    .collect(),
+            },
             Value::Proc {
                 is_fn,
                 args,
                 return_type,
                 content,
+                label,
+                ..
             } => Element::Procedure {
                 position: Default::default(),
                 raw: "".to_string(),
                 is_fn: *is_fn,
+                // the generic parameters are already in the types of the arguments
+                generics: vec![],
                 args: args.to_owned(),
                 return_type: return_type.to_owned(),
                 content: content.to_owned(),
+                label: label.to_owned(),
+            },
+            Value::Overload(procs) => Element::Overload {
+                position: Default::default(),
+                raw: self.to_string(),
+                procs: procs.iter().map(Value::as_element).collect(),
             },
             Value::Null => Element::NullElement,
-            Value::Return(v) => Element::Return {
+            Value::EnumVariant { value, .. } if matches!(**value, Value::ClassInstance { .. }) => {
+                todo!()
+            }
+            Value::EnumVariant { type_, variant, .. } => Element::Variable {
                 position: Default::default(),
-                raw: "".to_string(),
-                value: Box::new(v.as_element()),
+                name: variant.to_owned(),
+                raw: self.to_string(),
+                parent: Box::new(type_.as_element()),
             },
-            Value::ClassInstance { .. } => todo!(),
+            Value::ClassInstance { .. } | Value::Error(_) | Value::Ref { .. } => todo!(),
         }
     }
 }
@@ -1,6 +1,81 @@
 use crate::objects::value::utils::OprError;
 use crate::objects::value::Value;
+use crate::Type;
+
+/// Gets the item type of two collections being concatenated.
+/// An empty collection literal has items of type `_any`, so it concatenates with anything
+fn common_type(type1: &Type, type2: Type) -> Option<Type> {
+    if *type1 == Type::any() {
+        Some(type2)
+    } else if type2 == Type::any() || *type1 == type2 {
+        Some(type1.to_owned())
+    } else {
+        None
+    }
+}
 
 pub fn concat(x: &Value, y: Value) -> Result<Value, OprError> {
-    Ok(Value::Str(x.to_string() + &y.to_string()))
+    match (x, y) {
+        (
+            Value::Array {
+                is_vec,
+                type_: type1,
+                content: content1,
+            },
+            Value::Array {
+                type_: type2,
+                content: content2,
+                ..
+            },
+        ) => Ok(Value::Array {
+            is_vec: *is_vec,
+            type_: common_type(type1, type2).ok_or(OprError::NoImplForOpr)?,
+            content: content1.iter().cloned().chain(content2).collect(),
+        }),
+        (Value::Tuple(content1), Value::Tuple(content2)) => Ok(Value::Tuple(
+            content1.iter().cloned().chain(content2).collect(),
+        )),
+        (
+            Value::Set {
+                is_frozen,
+                type_: type1,
+                content: content1,
+            },
+            Value::Set {
+                type_: type2,
+                content: content2,
+                ..
+            },
+        ) => Ok(Value::Set {
+            is_frozen: *is_frozen,
+            type_: common_type(type1, type2).ok_or(OprError::NoImplForOpr)?,
+            content: content1.iter().cloned().chain(content2).collect(),
+        }),
+        (
+            Value::Dict {
+                key_type: key_type1,
+                value_type: value_type1,
+                content: content1,
+            },
+            Value::Dict {
+                key_type: key_type2,
+                value_type: value_type2,
+                content: content2,
+            },
+        ) => Ok(Value::Dict {
+            key_type: common_type(key_type1, key_type2).ok_or(OprError::NoImplForOpr)?,
+            value_type: common_type(value_type1, value_type2).ok_or(OprError::NoImplForOpr)?,
+            // values from the right override those from the left
+            content: content1
+                .iter()
+                .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                .chain(content2)
+                .collect(),
+        }),
+        (Value::Array { .. } | Value::Tuple(_) | Value::Set { .. } | Value::Dict { .. }, _)
+        | (_, Value::Array { .. } | Value::Tuple(_) | Value::Set { .. } | Value::Dict { .. }) => {
+            Err(OprError::NoImplForOpr)
+        }
+        (x, y) => Ok(Value::Str(x.to_string() + &y.to_string())),
+    }
 }
@@ -4,14 +4,28 @@ use crate::objects::value::Value;
 use crate::Type;
 use half::f16;
 use num::bigint::{ToBigInt, ToBigUint};
+use num::{CheckedDiv, Integer};
 
 macro_rules! typecast_div {
     ($e:ident, $t:ident, $s:literal, $x:ident, $y:ident, $zero:expr) => {{
         if $y.is_num() {
             let n = typecast(&$y, Value::Type(Type::from_name($s)))?.$t().unwrap().to_owned();
             if n == $zero {
-                // TODO undef / indet handling, but for now,
-                return Ok(Value::$e($x.to_owned()));
+                return Err(OprError::ValueError("division by zero".to_string()));
+            }
+            $x.checked_div(&n).map(Value::$e).ok_or_else(|| {
+                OprError::ValueError(format!("`{} / {}` is too large for `{}`", $x, n, $s))
+            })
+        } else {Err(OprError::NoImplForOpr)}
+    }};
+}
+
+macro_rules! typecast_float_div {
+    ($e:ident, $t:ident, $s:literal, $x:ident, $y:ident, $zero:expr) => {{
+        if $y.is_num() {
+            let n = typecast(&$y, Value::Type(Type::from_name($s)))?.$t().unwrap().to_owned();
+            if n == $zero {
+                return Err(OprError::ValueError("division by zero".to_string()));
             }
             Ok(Value::$e($x / n))
         } else {Err(OprError::NoImplForOpr)}
@@ -34,9 +48,124 @@ pub fn div(x: &Value, y: Value) -> Result<Value, OprError> {
         Value::U128(x) => typecast_div!(U128, as_u128, "u128", x, y, 0),
         Value::Usize(x) => typecast_div!(Usize, as_usize, "usize", x, y, 0),
         Value::Ubig(x) => typecast_div!(Ubig, as_ubig, "ubig", x, y, 0i32.to_biguint().unwrap()),
-        Value::F16(x) => typecast_div!(F16, as_f16, "f16", x, y, f16::from_f64(0.0)),
-        Value::F32(x) => typecast_div!(F32, as_f32, "f32", x, y, 0.0),
-        Value::F64(x) => typecast_div!(F64, as_f64, "f64", x, y, 0.0),
+        Value::F16(x) => typecast_float_div!(F16, as_f16, "f16", x, y, f16::from_f64(0.0)),
+        Value::F32(x) => typecast_float_div!(F32, as_f32, "f32", x, y, 0.0),
+        Value::F64(x) => typecast_float_div!(F64, as_f64, "f64", x, y, 0.0),
+        _ => Err(OprError::NoImplForOpr),
+    }
+}
+
+/// How the quotient of `/f`, `/c`, `/~`, `÷f`, `÷c` and `÷~` is rounded to a whole number
+#[derive(Clone, Copy)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+    /// To the nearest whole number, halves away from zero
+    Round,
+}
+
+/// The quotient of two integers rounded as given, or `None` if it overflows
+fn rounded_quotient<T: Integer + CheckedDiv + Clone>(
+    x: &T,
+    y: &T,
+    rounding: Rounding,
+) -> Option<T> {
+    let q = x.checked_div(y)?;
+    let r = x.to_owned() - q.to_owned() * y.to_owned();
+    if r.is_zero() {
+        return Some(q);
+    }
+    // `q` is truncated, so the exact quotient lies between it and the whole number after it away from zero
+    let negative = (r < T::zero()) != (*y < T::zero());
+    let away_from_zero = match rounding {
+        Rounding::Floor => negative,
+        Rounding::Ceil => !negative,
+        Rounding::Round => {
+            // the distance to the next multiple of `y` away from zero
+            let rest = if negative {
+                y.to_owned() + r.to_owned()
+            } else {
+                y.to_owned() - r.to_owned()
+            };
+            let abs = |n: T| if n < T::zero() { T::zero() - n } else { n };
+            abs(r) >= abs(rest)
+        }
+    };
+    Some(match (away_from_zero, negative) {
+        (false, _) => q,
+        (true, true) => q - T::one(),
+        (true, false) => q + T::one(),
+    })
+}
+
+macro_rules! typecast_rounded_div {
+    ($e:ident, $t:ident, $s:literal, $x:ident, $y:ident, $zero:expr, $rounding:ident) => {{
+        if $y.is_num() {
+            let n = typecast(&$y, Value::Type(Type::from_name($s)))?.$t().unwrap().to_owned();
+            if n == $zero {
+                return Err(OprError::ValueError("division by zero".to_string()));
+            }
+            rounded_quotient($x, &n, $rounding).map(Value::$e).ok_or_else(|| {
+                OprError::ValueError(format!("`{} / {}` is too large for `{}`", $x, n, $s))
+            })
+        } else {Err(OprError::NoImplForOpr)}
+    }};
+}
+
+macro_rules! typecast_rounded_float_div {
+    ($e:ident, $t:ident, $s:literal, $x:ident, $y:ident, $zero:expr, $rounding:ident) => {{
+        if $y.is_num() {
+            let n = typecast(&$y, Value::Type(Type::from_name($s)))?.$t().unwrap().to_owned();
+            if n == $zero {
+                return Err(OprError::ValueError("division by zero".to_string()));
+            }
+            let q = $x / n;
+            Ok(Value::$e(match $rounding {
+                Rounding::Floor => q.floor(),
+                Rounding::Ceil => q.ceil(),
+                Rounding::Round => q.round(),
+            }))
+        } else {Err(OprError::NoImplForOpr)}
+    }};
+}
+
+pub fn rounded_div(x: &Value, y: Value, rounding: Rounding) -> Result<Value, OprError> {
+    match x {
+        Value::I8(x) => typecast_rounded_div!(I8, as_i8, "i8", x, y, 0, rounding),
+        Value::I16(x) => typecast_rounded_div!(I16, as_i16, "i16", x, y, 0, rounding),
+        Value::I32(x) => typecast_rounded_div!(I32, as_i32, "i32", x, y, 0, rounding),
+        Value::I64(x) => typecast_rounded_div!(I64, as_i64, "i64", x, y, 0, rounding),
+        Value::I128(x) => typecast_rounded_div!(I128, as_i128, "i128", x, y, 0, rounding),
+        Value::Isize(x) => typecast_rounded_div!(Isize, as_isize, "isize", x, y, 0, rounding),
+        Value::Ibig(x) => typecast_rounded_div!(
+            Ibig,
+            as_ibig,
+            "ibig",
+            x,
+            y,
+            0i32.to_bigint().unwrap(),
+            rounding
+        ),
+        Value::U8(x) => typecast_rounded_div!(U8, as_u8, "u8", x, y, 0, rounding),
+        Value::U16(x) => typecast_rounded_div!(U16, as_u16, "u16", x, y, 0, rounding),
+        Value::U32(x) => typecast_rounded_div!(U32, as_u32, "u32", x, y, 0, rounding),
+        Value::U64(x) => typecast_rounded_div!(U64, as_u64, "u64", x, y, 0, rounding),
+        Value::U128(x) => typecast_rounded_div!(U128, as_u128, "u128", x, y, 0, rounding),
+        Value::Usize(x) => typecast_rounded_div!(Usize, as_usize, "usize", x, y, 0, rounding),
+        Value::Ubig(x) => typecast_rounded_div!(
+            Ubig,
+            as_ubig,
+            "ubig",
+            x,
+            y,
+            0i32.to_biguint().unwrap(),
+            rounding
+        ),
+        // `f16` cannot be rounded itself
+        Value::F16(x) => rounded_div(&Value::F32(x.to_f32()), y, rounding)
+            .map(|q| Value::F16(f16::from_f32(*q.as_f32().unwrap()))),
+        Value::F32(x) => typecast_rounded_float_div!(F32, as_f32, "f32", x, y, 0.0, rounding),
+        Value::F64(x) => typecast_rounded_float_div!(F64, as_f64, "f64", x, y, 0.0, rounding),
         _ => Err(OprError::NoImplForOpr),
     }
 }
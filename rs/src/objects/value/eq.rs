@@ -33,6 +33,33 @@ pub fn eq(x: &Value, y: Value) -> Result<Value, OprError> {
         Value::F32(x) => typecast_eq!(as_f32, "f32", x, y),
         Value::F64(x) => typecast_eq!(as_f64, "f64", x, y),
         Value::Bool(x) => typecast_eq!(as_bool, "bool", x, y),
+        // structs are equal if they are of the same struct and each of their fields are equal
+        Value::ClassInstance { type_, attrs } if type_.fields().is_some() => match y {
+            Value::ClassInstance {
+                type_: type2,
+                attrs: attrs2,
+            } if *type_ == type2 => {
+                let mut equal = true;
+                for (name, value) in attrs {
+                    equal &= *eq(value, attrs2[name].to_owned())?.as_bool().unwrap();
+                }
+                equal
+            }
+            _ => false,
+        },
+        // enum variants are equal if they are the same variant and their values are equal
+        Value::EnumVariant {
+            type_,
+            variant,
+            value,
+        } => match y {
+            Value::EnumVariant {
+                type_: type2,
+                variant: variant2,
+                value: value2,
+            } if *type_ == type2 && *variant == variant2 => *eq(value, *value2)?.as_bool().unwrap(),
+            _ => false,
+        },
         _ => *iseq(x, y)?.as_bool().unwrap(),
     }))
 }
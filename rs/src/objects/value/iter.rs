@@ -0,0 +1,46 @@
+use crate::interpreter::{call, get_attr};
+use crate::objects::control_flow::ControlFlow;
+use crate::objects::interpreter_data::Print;
+use crate::objects::position::Position;
+use crate::objects::value::Value;
+use crate::{InterpreterData, ZyxtError};
+use std::collections::HashMap;
+
+/// Gets the items of a value for a `for` loop.
+///
+/// Sequences iterate over their items. Class instances implement the protocol through an
+/// `_iter` method, which is called with the instance and returns another iterable value.
+pub fn iter<O: Print>(
+    x: &Value,
+    i_data: &mut InterpreterData<Value, O>,
+    position: &Position,
+    raw: &String,
+) -> Result<Vec<Value>, ControlFlow> {
+    match x {
+        Value::Str(s) => Ok(s.chars().map(|c| Value::Str(c.to_string())).collect()),
+        Value::Array { content, .. } | Value::Tuple(content) => Ok(content.to_owned()),
+        Value::Set { content, .. } => Ok(content.iter().cloned().collect()),
+        Value::Dict { content, .. } => Ok(content.keys().cloned().collect()),
+        Value::ClassInstance { attrs, type_ }
+            if attrs.contains_key("_iter") || type_.get_attrs().contains_key("_iter") =>
+        {
+            let iterable = call(
+                get_attr(x, &"_iter".to_string(), i_data, position, raw)?,
+                vec![x.to_owned()],
+                HashMap::new(),
+                i_data,
+                position,
+                raw,
+            )?;
+            if iterable == *x {
+                return Err(ZyxtError::error_4_5_1(x.to_owned())
+                    .with_pos_and_raw(position, raw)
+                    .into());
+            }
+            iter(&iterable, i_data, position, raw)
+        }
+        _ => Err(ZyxtError::error_4_5_1(x.to_owned())
+            .with_pos_and_raw(position, raw)
+            .into()),
+    }
+}
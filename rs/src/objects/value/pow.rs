@@ -1 +1,219 @@
+use crate::objects::value::typecast::typecast;
+use crate::objects::value::utils::OprError;
+use crate::objects::value::Value;
+use crate::Type;
+use half::f16;
+use num::{BigInt, Signed, ToPrimitive};
+use std::f64::consts::E;
 
+/// The type of the result of `rt` and `log`, which is the widest float type of the operands, or `f64` if neither is a float
+pub fn float_type(x: &Type, y: &Type) -> Type {
+    ["f64", "f32", "f16"]
+        .into_iter()
+        .map(Type::from_name)
+        .find(|t| t == x || t == y)
+        .unwrap_or_else(|| Type::from_name("f64"))
+}
+
+fn as_float(x: &Value) -> Result<f64, OprError> {
+    if !x.is_num() {
+        return Err(OprError::NoImplForOpr);
+    }
+    Ok(*typecast(x, Value::Type(Type::from_name("f64")))?
+        .as_f64()
+        .unwrap())
+}
+
+fn log_float(x: f64, base: f64) -> f64 {
+    if base == 2.0 {
+        x.log2()
+    } else if base == 10.0 {
+        x.log10()
+    } else {
+        x.log(base)
+    }
+}
+
+/// The logarithm of the magnitude of a number. Big integers too large for a float are cut down
+/// to their leading digits, in decimal for base 10 and in binary otherwise, and the digits cut off are added back
+fn log_abs(x: &Value, base: f64) -> Result<f64, OprError> {
+    let magnitude = match x {
+        Value::Ibig(n) => Some(n.magnitude().to_owned()),
+        Value::Ubig(n) => Some(n.to_owned()),
+        _ => None,
+    };
+    match magnitude {
+        Some(n) if n.bits() > 64 && base == 10.0 => {
+            let digits = n.to_string();
+            let leading = digits[..17].parse::<f64>().unwrap();
+            Ok(leading.log10() + (digits.len() - 17) as f64)
+        }
+        Some(n) if n.bits() > 64 => {
+            let shift = n.bits() - 64;
+            Ok(log_float((n >> shift).to_f64().unwrap(), base)
+                + shift as f64 * log_float(2.0, base))
+        }
+        _ => Ok(log_float(as_float(x)?.abs(), base)),
+    }
+}
+
+/// The exponent of a power of an integer, or `None` if it is a float, in which case the power is taken in floats
+fn int_exponent(y: &Value) -> Result<Option<u32>, OprError> {
+    match y {
+        Value::F16(_) | Value::F32(_) | Value::F64(_) => Ok(None),
+        _ if y.is_num() => {
+            let n: BigInt = typecast(y, Value::Type(Type::from_name("ibig")))?
+                .into_ibig()
+                .unwrap();
+            if n.is_negative() {
+                Err(OprError::ValueError(format!(
+                    "an integer cannot be raised to the negative power `{}`, use a float instead",
+                    y
+                )))
+            } else {
+                n.to_u32().map(Some).ok_or_else(|| {
+                    OprError::ValueError(format!("the exponent `{}` is too large", y))
+                })
+            }
+        }
+        _ => Err(OprError::NoImplForOpr),
+    }
+}
+
+/// The most bits the power of a big integer can have, so that huge powers raise an error instead of running out of memory
+const MAX_BIG_POW_BITS: u64 = 1 << 26;
+
+/// The exponent of a power of a big integer, as `int_exponent` gives it, if the power is not too large to work out
+fn big_exponent(x: &Value, y: &Value) -> Result<Option<u32>, OprError> {
+    let bits = match x {
+        Value::Ibig(n) => n.bits(),
+        Value::Ubig(n) => n.bits(),
+        _ => 0,
+    };
+    let exponent = int_exponent(y)?;
+    // `x ^ n` has at least `(bits - 1) * n` bits
+    if exponent.is_some_and(|n| bits.saturating_sub(1).saturating_mul(n as u64) > MAX_BIG_POW_BITS)
+    {
+        Err(OprError::ValueError(format!(
+            "`{} ^ {}` is too large to be worked out",
+            x, y
+        )))
+    } else {
+        Ok(exponent)
+    }
+}
+
+fn float_pow(x: f64, y: f64) -> Result<f64, OprError> {
+    if x == 0.0 && y < 0.0 {
+        Err(OprError::ValueError(
+            "zero cannot be raised to a negative power".to_string(),
+        ))
+    } else if x < 0.0 && y.fract() != 0.0 {
+        Err(OprError::ValueError(
+            "a negative number cannot be raised to a fractional power".to_string(),
+        ))
+    } else {
+        Ok(x.powf(y))
+    }
+}
+
+macro_rules! int_pow {
+    ($e:ident, $s:literal, $x:ident, $y:ident) => {{
+        match int_exponent(&$y)? {
+            Some(n) => $x.checked_pow(n).map(Value::$e).ok_or_else(|| {
+                OprError::ValueError(format!("`{} ^ {}` is too large for `{}`", $x, $y, $s))
+            }),
+            None => Ok(Value::F64(float_pow(*$x as f64, as_float(&$y)?)?)),
+        }
+    }};
+}
+
+pub fn pow(x: &Value, y: Value) -> Result<Value, OprError> {
+    match x {
+        Value::I8(x) => int_pow!(I8, "i8", x, y),
+        Value::I16(x) => int_pow!(I16, "i16", x, y),
+        Value::I32(x) => int_pow!(I32, "i32", x, y),
+        Value::I64(x) => int_pow!(I64, "i64", x, y),
+        Value::I128(x) => int_pow!(I128, "i128", x, y),
+        Value::Isize(x) => int_pow!(Isize, "isize", x, y),
+        Value::U8(x) => int_pow!(U8, "u8", x, y),
+        Value::U16(x) => int_pow!(U16, "u16", x, y),
+        Value::U32(x) => int_pow!(U32, "u32", x, y),
+        Value::U64(x) => int_pow!(U64, "u64", x, y),
+        Value::U128(x) => int_pow!(U128, "u128", x, y),
+        Value::Usize(x) => int_pow!(Usize, "usize", x, y),
+        Value::Ibig(big) => match big_exponent(x, &y)? {
+            Some(n) => Ok(Value::Ibig(big.pow(n))),
+            None => Ok(Value::F64(float_pow(big.to_f64().unwrap(), as_float(&y)?)?)),
+        },
+        Value::Ubig(big) => match big_exponent(x, &y)? {
+            Some(n) => Ok(Value::Ubig(big.pow(n))),
+            None => Ok(Value::F64(float_pow(big.to_f64().unwrap(), as_float(&y)?)?)),
+        },
+        Value::F16(x) => Ok(Value::F16(f16::from_f64(float_pow(
+            x.to_f64(),
+            as_float(&y)?,
+        )?))),
+        Value::F32(x) => Ok(Value::F32(float_pow(*x as f64, as_float(&y)?)? as f32)),
+        Value::F64(x) => Ok(Value::F64(float_pow(*x, as_float(&y)?)?)),
+        _ => Err(OprError::NoImplForOpr),
+    }
+}
+
+/// `x rt y`, the `x`th root of `y`
+pub fn root(x: &Value, y: Value) -> Result<Value, OprError> {
+    let (index, radicand) = (as_float(x)?, as_float(&y)?);
+    if index == 0.0 {
+        return Err(OprError::ValueError("there is no zeroth root".to_string()));
+    } else if radicand < 0.0 && (index.fract() != 0.0 || index % 2.0 == 0.0) {
+        // only odd roots of negative numbers are real
+        return Err(OprError::ValueError(format!(
+            "a negative number has no real root of index `{}`",
+            x
+        )));
+    }
+    // square and cube roots are exact for perfect squares and cubes, unlike `powf`
+    let magnitude = if !radicand.is_finite() {
+        // big integers too large for a float are rooted through their logarithm
+        (log_abs(&y, E)? / index).exp()
+    } else if index == 2.0 {
+        radicand.abs().sqrt()
+    } else if index == 3.0 {
+        radicand.abs().cbrt()
+    } else {
+        radicand.abs().powf(index.recip())
+    };
+    let result = if radicand < 0.0 {
+        -magnitude
+    } else {
+        magnitude
+    };
+    typecast(
+        &Value::F64(result),
+        Value::Type(float_type(&x.get_type_obj(), &y.get_type_obj())),
+    )
+}
+
+/// `x log y`, the logarithm of `y` to the base `x`
+pub fn log(x: &Value, y: Value) -> Result<Value, OprError> {
+    let (base, argument) = (as_float(x)?, as_float(&y)?);
+    if base <= 0.0 || base == 1.0 {
+        return Err(OprError::ValueError(format!(
+            "the base of a logarithm must be positive and not 1, but is `{}`",
+            x
+        )));
+    } else if argument <= 0.0 {
+        return Err(OprError::ValueError(format!(
+            "only positive numbers have a logarithm, but `{}` is not",
+            y
+        )));
+    }
+    typecast(
+        &Value::F64(if base.is_finite() {
+            log_abs(&y, base)?
+        } else {
+            log_abs(&y, E)? / log_abs(x, E)?
+        }),
+        Value::Type(float_type(&x.get_type_obj(), &y.get_type_obj())),
+    )
+}
@@ -0,0 +1,136 @@
+use crate::objects::position::Position;
+use crate::objects::value::typecast::typecast;
+use crate::objects::value::Value;
+use crate::{Type, ZyxtError};
+
+/// Gets the number of items in a collection
+pub fn len(x: &Value, position: &Position, raw: &String) -> Result<usize, ZyxtError> {
+    match x {
+        Value::Str(s) => Ok(s.chars().count()),
+        Value::Array { content, .. } | Value::Tuple(content) => Ok(content.len()),
+        Value::Set { content, .. } => Ok(content.len()),
+        Value::Dict { content, .. } => Ok(content.len()),
+        _ => Err(ZyxtError::error_4_8_0(x.get_type_obj()).with_pos_and_raw(position, raw)),
+    }
+}
+
+/// Converts an index into an offset from the start of the sequence.
+/// Negative indices count from the end, so the offset may still be out of range
+fn offset(
+    index: &Value,
+    len: usize,
+    position: &Position,
+    raw: &String,
+) -> Result<isize, ZyxtError> {
+    let offset = if index.is_num() && !matches!(index, Value::Bool(_)) {
+        typecast(index, Value::Type(Type::from_name("isize")))
+            .ok()
+            .and_then(|i| i.as_isize().copied())
+    } else {
+        return Err(ZyxtError::error_4_8_1(index.get_type_obj()).with_pos_and_raw(position, raw));
+    };
+    match offset {
+        Some(i) if i < 0 => Ok(i + len as isize),
+        Some(i) => Ok(i),
+        None => Err(ZyxtError::error_4_8_2(index.to_owned(), len).with_pos_and_raw(position, raw)),
+    }
+}
+
+fn checked_offset(
+    index: &Value,
+    len: usize,
+    position: &Position,
+    raw: &String,
+) -> Result<usize, ZyxtError> {
+    let i = offset(index, len, position, raw)?;
+    if i < 0 || i >= len as isize {
+        Err(ZyxtError::error_4_8_2(index.to_owned(), len).with_pos_and_raw(position, raw))
+    } else {
+        Ok(i as usize)
+    }
+}
+
+/// Gets the item of a sequence at an index, or the value of a dict at a key
+pub fn index(
+    x: &Value,
+    index: &Value,
+    position: &Position,
+    raw: &String,
+) -> Result<Value, ZyxtError> {
+    if let Value::Dict { content, .. } = x {
+        return content.get(index).cloned().ok_or_else(|| {
+            ZyxtError::error_4_8_5(index.to_owned()).with_pos_and_raw(position, raw)
+        });
+    } else if let Value::Set { .. } = x {
+        return Err(ZyxtError::error_4_8_0(x.get_type_obj()).with_pos_and_raw(position, raw));
+    }
+    let i = checked_offset(index, len(x, position, raw)?, position, raw)?;
+    Ok(match x {
+        Value::Str(s) => Value::Str(s.chars().nth(i).unwrap().to_string()),
+        Value::Array { content, .. } | Value::Tuple(content) => content[i].to_owned(),
+        _ => unreachable!(),
+    })
+}
+
+/// Gets the items of a sequence from `start` up to but not including `end`.
+/// A null bound is open, and bounds past either end of the sequence are clamped to it
+pub fn slice(
+    x: &Value,
+    start: &Value,
+    end: &Value,
+    position: &Position,
+    raw: &String,
+) -> Result<Value, ZyxtError> {
+    if !matches!(x, Value::Str(_) | Value::Array { .. }) {
+        return Err(ZyxtError::error_4_8_0(x.get_type_obj()).with_pos_and_raw(position, raw));
+    }
+    let len = len(x, position, raw)?;
+    let bound = |index: &Value, default: usize| -> Result<usize, ZyxtError> {
+        if *index == Value::Null {
+            Ok(default)
+        } else {
+            Ok(offset(index, len, position, raw)?.clamp(0, len as isize) as usize)
+        }
+    };
+    let start = bound(start, 0)?;
+    let end = bound(end, len)?.max(start);
+    Ok(match x {
+        Value::Str(s) => Value::Str(s.chars().skip(start).take(end - start).collect()),
+        Value::Array {
+            is_vec,
+            type_,
+            content,
+        } => Value::Array {
+            is_vec: *is_vec,
+            type_: type_.to_owned(),
+            content: content[start..end].to_vec(),
+        },
+        _ => unreachable!(),
+    })
+}
+
+/// Replaces the item of a vector at an index, or sets the value of a dict at a key
+pub fn set_index(
+    x: &mut Value,
+    index: &Value,
+    value: Value,
+    position: &Position,
+    raw: &String,
+) -> Result<(), ZyxtError> {
+    if let Value::Dict { content, .. } = x {
+        content.insert(index.to_owned(), value);
+        return Ok(());
+    }
+    let len = len(x, position, raw)?;
+    if let Value::Array {
+        is_vec: true,
+        content,
+        ..
+    } = x
+    {
+        content[checked_offset(index, len, position, raw)?] = value;
+        Ok(())
+    } else {
+        Err(ZyxtError::error_4_8_3(x.get_type_obj()).with_pos_and_raw(position, raw))
+    }
+}
@@ -1,3 +1,5 @@
+use crate::objects::element::Element;
+use crate::objects::value::eq::eq;
 use crate::objects::value::utils::OprError;
 use crate::objects::value::Value;
 use crate::Type;
@@ -12,7 +14,7 @@ macro_rules! typecast_str_to_num {
         if let Ok(x) = $x.parse::<$t>() {
             Ok(Value::$e(x))
         } else {
-            Err(OprError::TypecastError(Type::from_name($st)))
+            Err(OprError::TypecastError(Box::new(Type::from_name($st))))
         }
     };
 }
@@ -54,14 +56,14 @@ fn typecast_bool(x: bool, y: String) -> Result<Value, OprError> {
         "i64" => Ok(Value::I64(x as i64)),
         "i128" => Ok(Value::I128(x as i128)),
         "isize" => Ok(Value::Isize(x as isize)),
-        "ibig" => Ok(Value::Ibig(x.to_string().parse::<BigInt>().unwrap())),
+        "ibig" => Ok(Value::Ibig(BigInt::from(x as u8))),
         "u8" => Ok(Value::U8(x as u8)),
         "u16" => Ok(Value::U16(x as u16)),
         "u32" => Ok(Value::U32(x as u32)),
         "u64" => Ok(Value::U64(x as u64)),
         "u128" => Ok(Value::U128(x as u128)),
         "usize" => Ok(Value::Usize(x as usize)),
-        "ubig" => Ok(Value::Ubig(x.to_string().parse::<BigUint>().unwrap())),
+        "ubig" => Ok(Value::Ubig(BigUint::from(x as u8))),
         "f16" => Ok(Value::F16(f16::from(x as u8))),
         "f32" => Ok(Value::F32(x as u8 as f32)),
         "f64" => Ok(Value::F64(x as u8 as f64)),
@@ -74,7 +76,7 @@ macro_rules! simple_num_to_num {
         if let Ok(x) = <$t>::try_from($x) {
             Ok(Value::$e(x))
         } else {
-            Err(OprError::TypecastError(Type::from_name($st)))
+            Err(OprError::TypecastError(Box::new(Type::from_name($st))))
         }
     };
 }
@@ -537,11 +539,52 @@ pub fn typecast(x: &Value, y: Value) -> Result<Value, OprError> {
                         Value::F32(x) => typecast_f32(x, name),
                         Value::F64(x) => typecast_f64(x, name),
                         Value::Type(_) => Ok(Value::Type(Type::from_name("type"))),
+                        // enum variants are cast through their value, and without one are named by str
+                        Value::EnumVariant { value, .. }
+                            if !matches!(*value, Value::Null | Value::ClassInstance { .. }) =>
+                        {
+                            typecast(&value, Value::Type(Type::from_name(&name)))
+                        }
+                        Value::ClassInstance { .. } | Value::EnumVariant { .. }
+                            if name == "str" =>
+                        {
+                            Ok(Value::Str(x.to_string()))
+                        }
                         _ => Err(OprError::NoImplForOpr),
                     }
                 }
             }
             Type::Return(y) => typecast(x, Value::Type(*y)),
+            Type::Definition { .. } => match x {
+                Value::ClassInstance {
+                    type_:
+                        Type::Instance {
+                            implementation: Some(definition),
+                            ..
+                        },
+                    ..
+                } if definition.mro().unwrap_or_default().contains(&y) => Ok(x.to_owned()),
+                _ => Err(OprError::NoImplForOpr),
+            },
+            Type::Enum { .. } if x.get_type_obj() == y.instance_type() => Ok(x.to_owned()),
+            // values are cast to the first variant of the enum with an equal value
+            Type::Enum { ref variants, .. } => variants
+                .iter()
+                .find_map(|(variant, value)| {
+                    if let Element::Literal { type_, content, .. } = value {
+                        let value = Value::from_type_content(type_.to_owned(), content.to_owned());
+                        eq(x, value.to_owned())
+                            .is_ok_and(|equal| equal == Value::Bool(true))
+                            .then(|| Value::EnumVariant {
+                                type_: y.instance_type(),
+                                variant: variant.to_owned(),
+                                value: Box::new(value),
+                            })
+                    } else {
+                        None
+                    }
+                })
+                .ok_or(OprError::NoImplForOpr),
             _ => Err(OprError::NoImplForOpr),
         },
         _ => Err(OprError::NoImplForOpr),
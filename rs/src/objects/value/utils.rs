@@ -2,6 +2,8 @@ use crate::Type;
 
 #[derive(Debug)]
 pub enum OprError {
-    TypecastError(Type),
+    TypecastError(Box<Type>),
     NoImplForOpr,
+    /// The operator is implemented for the types of the operands but not their values, with the reason why
+    ValueError(String),
 }
@@ -1,7 +1,11 @@
 use crate::errors::ZyxtError;
-use crate::objects::element::{Argument, Condition, Element, VecElementRaw};
-use crate::objects::token::{get_order, Keyword, OprType, Side, Token, TokenCategory, TokenType};
-use crate::objects::typeobj::Type;
+use crate::objects::element::{Argument, Condition, Element, MatchArm, Pattern, VecElementRaw};
+use crate::objects::token::{
+    get_order, Associativity, Keyword, OprType, Side, Token, TokenCategory, TokenType,
+    DEFAULT_INFIX_ORDER,
+};
+use crate::objects::typeobj::{Param, Type};
+use indexmap::IndexMap;
 use std::cmp::min;
 use std::collections::HashMap;
 
@@ -58,6 +62,46 @@ fn catch_between(
     Ok(catcher)
 }
 
+/// Whether the elements at the cursor start a procedure or function type, `proc<[T, ...], R>`
+fn is_proc_type(elements: &[Element], cursor: usize) -> bool {
+    matches!(
+        elements.get(cursor..cursor + 3),
+        Some([
+            Element::Token(Token {
+                type_: TokenType::Keyword(Keyword::Proc | Keyword::Fn),
+                ..
+            }),
+            Element::Token(Token {
+                type_: TokenType::NormalOpr(OprType::Lt),
+                ..
+            }),
+            Element::Token(Token {
+                type_: TokenType::OpenSquareParen,
+                ..
+            })
+        ])
+    )
+}
+
+/// Leaves the tokens of a procedure or function type as they are, for `parse_type` to parse.
+/// The cursor is moved to the closing angle bracket
+fn skip_proc_type(
+    elements: &[Element],
+    cursor: &mut usize,
+    new_elements: &mut Vec<Element>,
+) -> Result<(), ZyxtError> {
+    let start = *cursor;
+    *cursor += 1;
+    catch_between(
+        TokenType::NormalOpr(OprType::Lt),
+        TokenType::NormalOpr(OprType::Gt),
+        elements,
+        cursor,
+    )?;
+    new_elements.extend_from_slice(&elements[start..=*cursor]);
+    Ok(())
+}
+
 fn base_split<T: Clone>(
     parser_fn: &dyn Fn(Vec<Element>) -> Result<T, ZyxtError>,
     default_val: Option<T>,
@@ -71,7 +115,10 @@ fn base_split<T: Clone>(
     let mut catcher: Vec<Element> = vec![];
     let mut paren_level = 0;
     for element in elements {
-        if let Element::Token(Token { type_, .. }) = element {
+        if let Element::Token(Token {
+            type_, categories, ..
+        }) = element
+        {
             if type_ == divider && paren_level == 0 {
                 if !ignore_empty && catcher.is_empty() {
                     todo!()
@@ -82,9 +129,10 @@ fn base_split<T: Clone>(
                 }
                 catcher.clear();
             } else {
-                if type_ == opening {
+                // dividers nested in any kind of brackets are skipped
+                if type_ == opening || categories.contains(&TokenCategory::OpenParen) {
                     paren_level += 1;
-                } else if type_ == closing {
+                } else if type_ == closing || categories.contains(&TokenCategory::CloseParen) {
                     paren_level -= 1;
                 }
                 catcher.push(element.to_owned());
@@ -137,6 +185,217 @@ fn split_between(
     )
 }
 
+/// Parses a type annotation, which may have type arguments in angle brackets
+fn parse_type(mut elements: Vec<Element>) -> Result<Type, ZyxtError> {
+    let raw = elements
+        .iter()
+        .map(|e| e.get_raw())
+        .collect::<Vec<String>>()
+        .join("");
+    // `T?` is a T or null, `T!?` is a T or an error
+    if elements.len() > 1 {
+        if let Some(Element::Token(Token {
+            type_: type_ @ (TokenType::QuestionMark | TokenType::ErrorableMark),
+            ..
+        })) = elements.last()
+        {
+            let is_errorable = *type_ == TokenType::ErrorableMark;
+            elements.pop();
+            let type_ = parse_type(elements)?;
+            return Ok(if is_errorable {
+                type_.errorable()
+            } else {
+                type_.nullable()
+            });
+        }
+    }
+    // types in declarations have already been parsed as variables
+    for element in elements.iter_mut() {
+        if let Element::Variable {
+            position,
+            raw,
+            name,
+            parent,
+        } = element
+        {
+            if **parent == Element::NullElement {
+                *element = Element::Token(Token {
+                    value: name.to_owned(),
+                    type_: TokenType::Variable,
+                    position: position.to_owned(),
+                    whitespace: raw.trim_end_matches(&**name).to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    // `proc<[T, ...], R>` and `fn<[T, ...], R>`
+    if let [Element::Token(Token {
+        type_: TokenType::Keyword(keyword @ (Keyword::Proc | Keyword::Fn)),
+        ..
+    }), Element::Token(Token {
+        type_: TokenType::NormalOpr(OprType::Lt),
+        ..
+    }), rest @ .., Element::Token(Token {
+        type_: TokenType::NormalOpr(OprType::Gt),
+        ..
+    })] = &elements[..]
+    {
+        let mut cursor = 0;
+        if is_proc_type(&elements, 0) {
+            let params = catch_between(
+                TokenType::OpenSquareParen,
+                TokenType::CloseSquareParen,
+                rest,
+                &mut cursor,
+            )?;
+            if let Some(Element::Token(Token {
+                type_: TokenType::Comma,
+                ..
+            })) = rest.get(cursor + 1)
+            {
+                let params = base_split(
+                    &parse_type,
+                    None,
+                    TokenType::Comma,
+                    TokenType::NormalOpr(OprType::Lt),
+                    TokenType::NormalOpr(OprType::Gt),
+                    params,
+                    false,
+                )?;
+                return Ok(Type::Proc {
+                    is_fn: *keyword == Keyword::Fn,
+                    params: params
+                        .into_iter()
+                        .map(|type_| Param {
+                            name: String::new(),
+                            type_,
+                            has_default: false,
+                        })
+                        .collect(),
+                    return_type: Box::new(parse_type(rest[cursor + 2..].to_vec())?),
+                });
+            }
+        }
+    }
+    if let Some((
+        Element::Token(Token {
+            type_: TokenType::Variable,
+            value,
+            ..
+        }),
+        rest,
+    )) = elements.split_first()
+    {
+        if rest.is_empty() {
+            return Ok(Type::from_name(value));
+        }
+        if let (
+            Some(Element::Token(Token {
+                type_: TokenType::NormalOpr(OprType::Lt),
+                ..
+            })),
+            Some(Element::Token(Token {
+                type_: TokenType::NormalOpr(OprType::Gt),
+                ..
+            })),
+        ) = (rest.first(), rest.last())
+        {
+            if rest.len() > 2 {
+                let type_args = base_split(
+                    &parse_type,
+                    None,
+                    TokenType::Comma,
+                    TokenType::NormalOpr(OprType::Lt),
+                    TokenType::NormalOpr(OprType::Gt),
+                    rest[1..rest.len() - 1].to_vec(),
+                    false,
+                )?;
+                return Ok(Type::generic(value, type_args));
+            }
+        }
+    }
+    Err(if let Some(first) = elements.first() {
+        ZyxtError::error_2_1_0(raw.to_owned()).with_pos_and_raw(first.get_pos(), &raw)
+    } else {
+        ZyxtError::error_2_1_0(raw)
+    })
+}
+
+/// Parses the generic parameters of a procedure, class or struct in angle brackets, eg `<T, U>`
+fn get_generics(
+    cursor: &mut usize,
+    elements: &[Element],
+    raw: &mut String,
+) -> Result<Vec<Argument>, ZyxtError> {
+    let contents = catch_between(
+        TokenType::NormalOpr(OprType::Lt),
+        TokenType::NormalOpr(OprType::Gt),
+        elements,
+        cursor,
+    )?;
+    let contents_raw = contents
+        .iter()
+        .map(|e| e.get_raw())
+        .collect::<Vec<String>>()
+        .join("");
+    *raw = format!("{}{}{}", raw, contents_raw, elements[*cursor].get_raw());
+    contents
+        .split(|e| {
+            matches!(
+                e,
+                Element::Token(Token {
+                    type_: TokenType::Comma,
+                    ..
+                })
+            )
+        })
+        .map(|generic| {
+            if let [Element::Token(Token {
+                type_: TokenType::Variable,
+                value,
+                ..
+            })] = generic
+            {
+                Ok(Argument {
+                    name: value.to_owned(),
+                    type_: Type::any(),
+                    default: None,
+                })
+            } else {
+                Err(ZyxtError::error_2_1_0(contents_raw.to_owned())
+                    .with_pos_and_raw(elements[*cursor].get_pos(), &contents_raw))
+            }
+        })
+        .collect()
+}
+
+/// Parses the return type of a procedure, from the colon after its arguments up to its body.
+/// The cursor is left on the body
+fn get_return_type(
+    cursor: &mut usize,
+    elements: &[Element],
+    raw: &mut String,
+) -> Result<Type, ZyxtError> {
+    let mut selected = &elements[*cursor];
+    let mut catcher = vec![];
+    *raw = format!("{}{}", raw, selected.get_raw());
+    loop {
+        if *cursor == elements.len() - 1 {
+            return Err(ZyxtError::error_2_1_0(selected.get_raw())
+                .with_pos_and_raw(selected.get_pos(), &selected.get_raw()));
+        }
+        *cursor += 1;
+        selected = &elements[*cursor];
+        *raw = format!("{}{}", raw, selected.get_raw());
+        if let Element::Block { .. } = selected {
+            break;
+        }
+        catcher.push(selected.to_owned());
+    }
+    parse_type(catcher)
+}
+
 fn get_arguments(
     cursor: &mut usize,
     elements: &[Element],
@@ -153,48 +412,209 @@ fn get_arguments(
             .join(""),
         elements[*cursor].get_raw()
     );
-    base_split(
-        &|raw_arg| {
-            let parts = split_between(
-                TokenType::Colon,
-                TokenType::Null,
-                TokenType::Null,
-                raw_arg.to_owned(),
-                true,
-            )?;
-            let name = if let Some(Element::Variable { name, .. }) = parts.get(0) {
-                name.to_owned()
-            } else {
-                return Err(ZyxtError::error_2_1_15(",".to_string())
-                    .with_pos_and_raw(parts.get(0).unwrap().get_pos(), &raw_arg.get_raw()));
-            };
-            let type_ = if let Some(t) = parts.get(1) {
-                t.to_owned()
+    if contents.is_empty() {
+        return Ok(vec![]);
+    }
+    // each argument is split into its name, type and default value.
+    // commas in the angle brackets of the type don't separate arguments
+    let mut raw_args: Vec<Vec<Vec<Element>>> = vec![vec![vec![]]];
+    let mut paren_level = 0;
+    let mut angle_level = 0;
+    for ele in contents {
+        let raw_arg = raw_args.last_mut().unwrap();
+        if let Element::Token(Token {
+            type_, categories, ..
+        }) = &ele
+        {
+            if paren_level == 0 && angle_level == 0 {
+                if *type_ == TokenType::Comma {
+                    raw_args.push(vec![vec![]]);
+                    continue;
+                } else if *type_ == TokenType::Colon {
+                    raw_arg.push(vec![]);
+                    continue;
+                } else if raw_arg.len() == 2
+                    && [
+                        TokenType::NormalOpr(OprType::Elvis),
+                        TokenType::NormalOpr(OprType::ErrorHandle),
+                    ]
+                    .contains(type_)
+                {
+                    // `?:` or `!?:` after a type is the end of a nullable or errorable type,
+                    // followed by the default value
+                    if let Element::Token(token) = &ele {
+                        let is_errorable = *type_ == TokenType::NormalOpr(OprType::ErrorHandle);
+                        raw_arg.last_mut().unwrap().push(Element::Token(Token {
+                            value: if is_errorable { "!?" } else { "?" }.to_string(),
+                            type_: if is_errorable {
+                                TokenType::ErrorableMark
+                            } else {
+                                TokenType::QuestionMark
+                            },
+                            categories: &[],
+                            ..token.to_owned()
+                        }));
+                    }
+                    raw_arg.push(vec![]);
+                    continue;
+                }
+            }
+            if categories.contains(&TokenCategory::OpenParen) {
+                paren_level += 1;
+            } else if categories.contains(&TokenCategory::CloseParen) {
+                paren_level -= 1;
+            } else if raw_arg.len() == 2 && *type_ == TokenType::NormalOpr(OprType::Lt) {
+                angle_level += 1;
+            } else if raw_arg.len() == 2 && *type_ == TokenType::NormalOpr(OprType::Gt) {
+                angle_level -= 1;
+            }
+        }
+        raw_arg.last_mut().unwrap().push(ele);
+    }
+    raw_args
+        .into_iter()
+        .map(|mut parts| {
+            let arg_raw = parts
+                .iter()
+                .flatten()
+                .map(|e| e.get_raw())
+                .collect::<Vec<String>>()
+                .join("");
+            let name = if let [Element::Token(Token {
+                type_: TokenType::Variable,
+                value,
+                ..
+            })] = &parts[0][..]
+            {
+                value.to_owned()
             } else {
-                Element::NullElement
+                let position = parts[0]
+                    .first()
+                    .map(|e| e.get_pos().to_owned())
+                    .unwrap_or_default();
+                return Err(ZyxtError::error_2_1_15(arg_raw.to_owned())
+                    .with_pos_and_raw(&position, &arg_raw));
             };
-            let default = parts.get(2).cloned();
             if parts.len() > 3 {
-                return Err(ZyxtError::error_2_1_14(parts[3].get_raw())
-                    .with_pos_and_raw(parts.get(3).unwrap().get_pos(), &raw_arg.get_raw()));
+                let extra = parts[3]
+                    .iter()
+                    .map(|e| e.get_raw())
+                    .collect::<Vec<String>>()
+                    .join("");
+                return Err(ZyxtError::error_2_1_14(extra)
+                    .with_pos_and_raw(parts[3][0].get_pos(), &arg_raw));
             }
+            let default = if parts.len() == 3 {
+                Some(parse_expr(parts.pop().unwrap())?)
+            } else {
+                None
+            };
             Ok(Argument {
                 name,
-                type_: if type_ == Element::NullElement {
-                    Type::any()
+                type_: if parts.len() == 2 {
+                    parse_type(parts.pop().unwrap())?
                 } else {
-                    type_.as_type()
+                    Type::any()
                 },
                 default,
             })
-        },
+        })
+        .collect()
+}
+
+/// Splits the arguments of a call, separating out the keyword arguments (`name: value`)
+fn get_call_args(
+    opening: TokenType,
+    closing: TokenType,
+    elements: Vec<Element>,
+) -> Result<(Vec<Element>, HashMap<String, Element>), ZyxtError> {
+    let raw_args = base_split(
+        &|raw_arg| Ok(raw_arg),
         None,
         TokenType::Comma,
-        TokenType::Bar,
-        TokenType::Bar,
-        contents,
+        opening,
+        closing,
+        elements,
         false,
-    )
+    )?;
+    let mut args = vec![];
+    let mut kwargs = HashMap::new();
+    for raw_arg in raw_args {
+        // the name may already have been parsed into a variable
+        let name = match raw_arg.first() {
+            Some(Element::Token(Token {
+                type_: TokenType::Variable,
+                value,
+                ..
+            })) => Some(value),
+            Some(Element::Variable { name, parent, .. }) if **parent == Element::NullElement => {
+                Some(name)
+            }
+            _ => None,
+        };
+        if let (
+            Some(name),
+            Some(Element::Token(Token {
+                type_: TokenType::Colon,
+                ..
+            })),
+        ) = (name, raw_arg.get(1))
+        {
+            let value = parse_expr(raw_arg[2..].to_vec())?;
+            if kwargs.insert(name.to_owned(), value).is_some() {
+                let raw = raw_arg
+                    .iter()
+                    .map(|e| e.get_raw())
+                    .collect::<Vec<String>>()
+                    .join("");
+                return Err(ZyxtError::error_2_5_2(name.to_owned())
+                    .with_pos_and_raw(raw_arg[0].get_pos(), &raw));
+            }
+        } else {
+            let arg = parse_expr(raw_arg)?;
+            if !kwargs.is_empty() {
+                return Err(
+                    ZyxtError::error_2_1_24(arg.get_raw().trim().to_string()).with_element(&arg)
+                );
+            }
+            args.push(arg);
+        }
+    }
+    Ok((args, kwargs))
+}
+
+fn get_label(
+    cursor: &mut usize,
+    elements: &[Element],
+    raw: &mut String,
+) -> Result<Option<String>, ZyxtError> {
+    if let Some(
+        apostrophe @ Element::Token(Token {
+            type_: TokenType::Apostrophe,
+            ..
+        }),
+    ) = elements.get(*cursor + 1)
+    {
+        if let Some(Element::Token(Token {
+            type_: TokenType::Variable,
+            value,
+            ..
+        })) = elements.get(*cursor + 2)
+        {
+            *raw = format!(
+                "{}{}{}",
+                raw,
+                apostrophe.get_raw(),
+                elements[*cursor + 2].get_raw()
+            );
+            *cursor += 2;
+            Ok(Some(value.to_owned()))
+        } else {
+            Err(ZyxtError::error_2_1_0(apostrophe.get_raw()).with_element(apostrophe))
+        }
+    } else {
+        Ok(None)
+    }
 }
 
 fn parse_parens(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
@@ -203,7 +623,9 @@ fn parse_parens(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
     let mut new_elements: Vec<Element> = vec![];
     while cursor < elements.len() {
         selected = &elements[cursor];
-        if let Element::Token(selected) = selected {
+        if is_proc_type(&elements, cursor) {
+            skip_proc_type(&elements, &mut cursor, &mut new_elements)?;
+        } else if let Element::Token(selected) = selected {
             if selected.type_ == TokenType::OpenParen {
                 let mut prev_element = &Element::Token(Token {
                     ..Default::default()
@@ -227,6 +649,129 @@ fn parse_parens(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
                 } else {
                     new_elements.push(Element::Token(selected.to_owned()))
                 }
+            } else if selected.type_ == TokenType::OpenSquareParen {
+                let prev_element = if cursor != 0 {
+                    Some(&elements[cursor - 1])
+                } else {
+                    None
+                };
+                let kind = if let Some(Element::Token(Token {
+                    type_: TokenType::Variable,
+                    value,
+                    ..
+                })) = prev_element
+                {
+                    ["vec", "tuple", "set", "fset", "dict"]
+                        .contains(&&**value)
+                        .then(|| value.to_owned())
+                } else {
+                    None
+                };
+                let is_index = match prev_element {
+                    Some(Element::Token(prev_element)) => {
+                        kind.is_none() && prev_element.categories.contains(&TokenCategory::ValueEnd)
+                    }
+                    Some(_) => true,
+                    None => false,
+                };
+                if !is_index {
+                    // collections, [ / vec[ / tuple[ / set[ / fset[ / dict[
+                    let (position, mut raw) = if kind.is_some() {
+                        let kind_token = new_elements.pop().unwrap();
+                        (kind_token.get_pos().to_owned(), kind_token.get_raw())
+                    } else {
+                        (selected.position.to_owned(), "".to_string())
+                    };
+                    raw = format!("{}{}", raw, selected.get_raw());
+                    let contents = catch_between(
+                        TokenType::OpenSquareParen,
+                        TokenType::CloseSquareParen,
+                        &elements,
+                        &mut cursor,
+                    )?;
+                    raw = format!(
+                        "{}{}{}",
+                        raw,
+                        contents
+                            .iter()
+                            .map(|e| e.get_raw())
+                            .collect::<Vec<String>>()
+                            .join(""),
+                        elements[cursor].get_raw()
+                    );
+                    new_elements.push(if kind.as_deref() == Some("dict") {
+                        Element::Dict {
+                            position,
+                            raw,
+                            content: base_split(
+                                &|entry| {
+                                    let colon_pos = entry.iter().position(|ele| {
+                                        matches!(
+                                            ele,
+                                            Element::Token(Token {
+                                                type_: TokenType::Colon,
+                                                ..
+                                            })
+                                        )
+                                    });
+                                    if let Some(colon_pos) = colon_pos {
+                                        Ok((
+                                            parse_expr(entry[..colon_pos].to_vec())?,
+                                            parse_expr(entry[colon_pos + 1..].to_vec())?,
+                                        ))
+                                    } else {
+                                        Err(ZyxtError::error_2_1_23(
+                                            entry
+                                                .iter()
+                                                .map(|e| e.get_raw())
+                                                .collect::<Vec<String>>()
+                                                .join("")
+                                                .trim()
+                                                .to_string(),
+                                        )
+                                        .with_element(&entry[0]))
+                                    }
+                                },
+                                None,
+                                TokenType::Comma,
+                                TokenType::OpenSquareParen,
+                                TokenType::CloseSquareParen,
+                                contents,
+                                false,
+                            )?,
+                        }
+                    } else {
+                        let content = split_between(
+                            TokenType::Comma,
+                            TokenType::OpenSquareParen,
+                            TokenType::CloseSquareParen,
+                            contents,
+                            false,
+                        )?;
+                        match kind.as_deref() {
+                            Some("tuple") => Element::Tuple {
+                                position,
+                                raw,
+                                content,
+                            },
+                            Some("set") | Some("fset") => Element::SetLiteral {
+                                position,
+                                raw,
+                                is_frozen: kind.as_deref() == Some("fset"),
+                                content,
+                            },
+                            _ => Element::Array {
+                                position,
+                                raw,
+                                is_vec: kind.is_some(),
+                                content,
+                            },
+                        }
+                    });
+                } else {
+                    // or else it's an index
+                    new_elements.push(Element::Token(selected.to_owned()))
+                }
             } else if selected.type_ == TokenType::OpenCurlyParen {
                 // blocks, {
                 let raw = selected.get_raw();
@@ -340,15 +885,46 @@ fn parse_classes_structs_and_mixins(elements: Vec<Element>) -> Result<Vec<Elemen
     while cursor < elements.len() {
         selected = &elements[cursor];
         if let Element::Token(Token {
+            type_: TokenType::RangeOpr,
+            position,
+            ..
+        }) = selected
+        {
+            // `..Parent` at the start of a statement in a class inherits from Parent
+            if cursor == 0 && elements.len() > 1 {
+                let parent = parse_expr(elements[1..].to_vec())?;
+                new_elements.push(Element::Inherit {
+                    position: position.to_owned(),
+                    raw: elements
+                        .iter()
+                        .map(|e| e.get_raw())
+                        .collect::<Vec<String>>()
+                        .join(""),
+                    parent: Box::new(parent),
+                });
+                break;
+            }
+            new_elements.push(selected.to_owned())
+        } else if let Element::Token(Token {
             type_: TokenType::Keyword(keyword),
             position,
             ..
         }) = selected
         {
-            if [Keyword::Class, Keyword::Struct].contains(keyword) {
+            if [Keyword::Class, Keyword::Struct, Keyword::Mixin].contains(keyword) {
                 let mut raw = selected.get_raw();
                 check_and_update_cursor!(cursor, selected, elements);
 
+                let mut generics = vec![];
+                if let Element::Token(Token {
+                    type_: TokenType::NormalOpr(OprType::Lt),
+                    ..
+                }) = selected
+                {
+                    raw = format!("{}{}", raw, selected.get_raw());
+                    generics = get_generics(&mut cursor, &elements, &mut raw)?;
+                    check_and_update_cursor!(cursor, selected, elements);
+                }
                 let mut args = None;
                 if let Element::Token(Token {
                     type_: TokenType::Bar,
@@ -357,12 +933,22 @@ fn parse_classes_structs_and_mixins(elements: Vec<Element>) -> Result<Vec<Elemen
                     ..
                 }) = selected
                 {
-                    if keyword == &Keyword::Class {
-                        return Err(ZyxtError::error_2_1_17()
-                            .with_pos_and_raw(position, &format!("class {}", value.trim())));
+                    if keyword != &Keyword::Struct {
+                        let kwd = raw.trim().to_string();
+                        return Err(ZyxtError::error_2_1_17(&kwd)
+                            .with_pos_and_raw(position, &format!("{} {}", kwd, value.trim())));
+                    }
+                    raw = format!("{}{}", raw, selected.get_raw());
+                    let mut fields = get_arguments(&mut cursor, &elements, &mut raw)?;
+                    for field in fields.iter_mut() {
+                        field.type_ = field.type_.to_owned().with_generics(&generics);
+                    }
+                    args = Some(fields);
+                    // structs can leave out their block
+                    if let Some(block @ Element::Block { .. }) = elements.get(cursor + 1) {
+                        cursor += 1;
+                        selected = block;
                     }
-                    args = Some(get_arguments(&mut cursor, &elements, &mut raw)?);
-                    check_and_update_cursor!(cursor, selected, elements);
                 }
                 let mut content = vec![];
                 if let Element::Block {
@@ -373,7 +959,7 @@ fn parse_classes_structs_and_mixins(elements: Vec<Element>) -> Result<Vec<Elemen
                 {
                     content = block_content.to_owned();
                     raw = format!("{}{}", raw, block_raw);
-                } else if keyword == &Keyword::Class {
+                } else if keyword != &Keyword::Struct {
                     return Err(ZyxtError::error_2_1_18(keyword).with_pos_and_raw(
                         selected.get_pos(),
                         &format!("{}{}", raw, &selected.get_raw()),
@@ -382,7 +968,10 @@ fn parse_classes_structs_and_mixins(elements: Vec<Element>) -> Result<Vec<Elemen
                 new_elements.push(Element::Class {
                     position: position.to_owned(),
                     raw,
+                    name: None,
                     is_struct: keyword == &Keyword::Struct,
+                    is_mixin: keyword == &Keyword::Mixin,
+                    generics,
                     class_attrs: Default::default(),
                     inst_attrs: Default::default(),
                     content,
@@ -399,6 +988,73 @@ fn parse_classes_structs_and_mixins(elements: Vec<Element>) -> Result<Vec<Elemen
     Ok(new_elements)
 }
 
+fn parse_enums(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
+    let mut cursor = 0;
+    let mut selected;
+    let mut new_elements = vec![];
+
+    while cursor < elements.len() {
+        selected = &elements[cursor];
+        if let Element::Token(Token {
+            type_: TokenType::Keyword(keyword @ Keyword::Enum),
+            position,
+            ..
+        }) = selected
+        {
+            let mut raw = selected.get_raw();
+            check_and_update_cursor!(cursor, selected, elements);
+
+            let content = if let Element::Block {
+                content,
+                raw: block_raw,
+                ..
+            } = selected
+            {
+                raw = format!("{}{}", raw, block_raw);
+                content
+            } else {
+                return Err(ZyxtError::error_2_1_18(keyword).with_pos_and_raw(
+                    selected.get_pos(),
+                    &format!("{}{}", raw, &selected.get_raw()),
+                ));
+            };
+            // each variant is either a name, or a name declared as its value or struct
+            let mut variants = IndexMap::new();
+            for item in content {
+                let (variant, value) = match item {
+                    Element::NullElement => continue,
+                    Element::Variable { name, parent, .. } if **parent == Element::NullElement => {
+                        (name.to_owned(), Element::NullElement)
+                    }
+                    Element::Declare {
+                        variable,
+                        content,
+                        flags,
+                        ..
+                    } if variable.is_pattern() && flags.is_empty() => {
+                        (variable.get_name(), *content.to_owned())
+                    }
+                    _ => return Err(ZyxtError::error_2_6_3().with_element(item)),
+                };
+                if variants.contains_key(&variant) {
+                    return Err(ZyxtError::error_2_6_4(variant).with_element(item));
+                }
+                variants.insert(variant, value);
+            }
+            new_elements.push(Element::Enum {
+                position: position.to_owned(),
+                raw,
+                name: None,
+                variants,
+            })
+        } else {
+            new_elements.push(selected.to_owned())
+        }
+        cursor += 1;
+    }
+    Ok(new_elements)
+}
+
 fn parse_vars_literals_and_calls(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
     let mut cursor = 0;
     let mut selected;
@@ -407,9 +1063,15 @@ fn parse_vars_literals_and_calls(elements: Vec<Element>) -> Result<Vec<Element>,
 
     while cursor < elements.len() {
         selected = &elements[cursor];
-        if let Element::Token(selected) = selected {
+        if is_proc_type(&elements, cursor) {
+            if catcher != Element::NullElement {
+                new_elements.push(catcher.to_owned());
+            }
+            catcher = Element::NullElement;
+            skip_proc_type(&elements, &mut cursor, &mut new_elements)?;
+        } else if let Element::Token(selected) = selected {
             match selected.type_ {
-                TokenType::DotOpr => {
+                TokenType::DotOpr | TokenType::SafeDotOpr => {
                     // TODO rewrite this
                     if cursor == 0 {
                         return Err(ZyxtError::error_2_1_0(String::from("."))
@@ -430,6 +1092,14 @@ fn parse_vars_literals_and_calls(elements: Vec<Element>) -> Result<Vec<Element>,
                             ZyxtError::error_2_1_0(content.to_owned()).with_element(next_element)
                         );
                     }
+                    // the parent of an attribute accessed with `?.` may be null
+                    if selected.type_ == TokenType::SafeDotOpr {
+                        catcher = Element::NullSafe {
+                            position: selected.position.to_owned(),
+                            raw: catcher.get_raw(),
+                            operand: Box::new(catcher),
+                        };
+                    }
                     if let (Element::Token(prev_element), Element::Token(next_element)) =
                         (prev_element, next_element)
                     {
@@ -462,21 +1132,36 @@ fn parse_vars_literals_and_calls(elements: Vec<Element>) -> Result<Vec<Element>,
                             ),
                             parent: Box::new(catcher),
                         };
+                        cursor += 1;
                     } else {
                         return Err(ZyxtError::error_2_1_0(String::from(".")).with_token(selected));
                         // definitely at the wrong place
                     }
                 }
+                // `x!!` is checked before its attributes are accessed, as in `x!!.y`
+                TokenType::UnaryOpr(OprType::Assert, Side::Right)
+                    if catcher != Element::NullElement =>
+                {
+                    catcher = Element::UnaryOpr {
+                        position: selected.position.to_owned(),
+                        raw: format!("{}{}", catcher.get_raw(), selected.get_raw()),
+                        type_: OprType::Assert,
+                        operand: Box::new(catcher),
+                    }
+                }
                 TokenType::Variable => {
                     if catcher != Element::NullElement {
                         new_elements.push(catcher.to_owned());
                     }
-                    catcher = Element::Variable {
-                        position: selected.position.to_owned(),
-                        name: selected.value.to_owned(),
-                        raw: selected.get_raw(),
-                        parent: Box::new(Element::NullElement),
-                    }
+                    catcher = apply_derefs(
+                        &mut new_elements,
+                        Element::Variable {
+                            position: selected.position.to_owned(),
+                            name: selected.value.to_owned(),
+                            raw: selected.get_raw(),
+                            parent: Box::new(Element::NullElement),
+                        },
+                    )
                 }
                 TokenType::LiteralNumber | TokenType::LiteralMisc | TokenType::LiteralString => {
                     if catcher != Element::NullElement {
@@ -519,6 +1204,60 @@ fn parse_vars_literals_and_calls(elements: Vec<Element>) -> Result<Vec<Element>,
                 TokenType::CloseParen => {
                     return Err(ZyxtError::error_2_0_2(')'.to_string()).with_token(selected))
                 }
+                TokenType::CloseSquareParen => {
+                    return Err(ZyxtError::error_2_0_2(']'.to_string()).with_token(selected))
+                }
+                TokenType::OpenSquareParen => {
+                    if cursor == 0 {
+                        return Err(ZyxtError::error_2_1_0(String::from("[")).with_token(selected));
+                        // array literals should have been settled in the first part
+                    }
+                    let contents = catch_between(
+                        TokenType::OpenSquareParen,
+                        TokenType::CloseSquareParen,
+                        &elements,
+                        &mut cursor,
+                    )?;
+                    let raw = format!(
+                        "{}{}{}{}",
+                        catcher.get_raw(),
+                        selected.get_raw(),
+                        contents
+                            .iter()
+                            .map(|e| e.get_raw())
+                            .collect::<Vec<String>>()
+                            .join(""),
+                        elements[cursor].get_raw()
+                    );
+                    let mut bounds = base_split(
+                        &parse_expr,
+                        Some(Element::NullElement),
+                        TokenType::RangeOpr,
+                        TokenType::OpenSquareParen,
+                        TokenType::CloseSquareParen,
+                        contents,
+                        true,
+                    )?;
+                    catcher = match bounds.len() {
+                        1 => Element::Index {
+                            position: selected.position.to_owned(),
+                            raw,
+                            parent: Box::new(catcher),
+                            index: Box::new(bounds.remove(0)),
+                        },
+                        2 => Element::Slice {
+                            position: selected.position.to_owned(),
+                            raw,
+                            start: Box::new(bounds.remove(0)),
+                            end: Box::new(bounds.remove(0)),
+                            parent: Box::new(catcher),
+                        },
+                        _ => {
+                            return Err(ZyxtError::error_2_1_0(raw.to_owned())
+                                .with_pos_and_raw(&selected.position, &raw))
+                        }
+                    }
+                }
                 TokenType::OpenParen => {
                     if cursor == 0 {
                         return Err(ZyxtError::error_2_1_0(String::from("(")).with_token(selected));
@@ -541,19 +1280,14 @@ fn parse_vars_literals_and_calls(elements: Vec<Element>) -> Result<Vec<Element>,
                             .join(""),
                         elements[cursor].get_raw()
                     );
-                    let args = split_between(
-                        TokenType::Comma,
-                        TokenType::OpenParen,
-                        TokenType::CloseParen,
-                        contents,
-                        false,
-                    )?;
+                    let (args, kwargs) =
+                        get_call_args(TokenType::OpenParen, TokenType::CloseParen, contents)?;
                     catcher = Element::Call {
                         position: selected.position.to_owned(),
                         raw: format!("{}{}", catcher.get_raw(), raw),
                         called: Box::new(catcher),
                         args,
-                        kwargs: HashMap::new(),
+                        kwargs,
                     }
                 }
                 _ => {
@@ -568,7 +1302,7 @@ fn parse_vars_literals_and_calls(elements: Vec<Element>) -> Result<Vec<Element>,
             if catcher != Element::NullElement {
                 new_elements.push(catcher.to_owned());
             }
-            catcher = selected.to_owned()
+            catcher = apply_derefs(&mut new_elements, selected.to_owned())
         }
         cursor += 1;
     }
@@ -579,6 +1313,27 @@ fn parse_vars_literals_and_calls(elements: Vec<Element>) -> Result<Vec<Element>,
     Ok(new_elements)
 }
 
+/// Dereferences an element by the `\` before it, which bind tighter than the attributes, items and calls
+/// after it, so that `\p[0]` indexes what `p` points to
+fn apply_derefs(new_elements: &mut Vec<Element>, mut element: Element) -> Element {
+    while let Some(Element::Token(
+        deref @ Token {
+            type_: TokenType::UnaryOpr(OprType::Deref, Side::Left),
+            ..
+        },
+    )) = new_elements.last()
+    {
+        element = Element::UnaryOpr {
+            position: deref.position.to_owned(),
+            raw: format!("{}{}", deref.get_raw(), element.get_raw()),
+            type_: OprType::Deref,
+            operand: Box::new(element),
+        };
+        new_elements.pop();
+    }
+    element
+}
+
 fn parse_procs_and_fns(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
     let mut new_elements = vec![];
     let mut cursor = 0;
@@ -586,6 +1341,51 @@ fn parse_procs_and_fns(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError
 
     while cursor < elements.len() {
         selected = &elements[cursor];
+        if is_proc_type(&elements, cursor) {
+            skip_proc_type(&elements, &mut cursor, &mut new_elements)?;
+            cursor += 1;
+            continue;
+        }
+        // the order of an infix procedure was taken before parsing, by `mark_infix_procs`
+        let mut infix_raw = None;
+        if let Element::Token(Token {
+            type_: TokenType::Keyword(Keyword::Infix),
+            ..
+        }) = selected
+        {
+            let mut raw = selected.get_raw();
+            check_and_update_cursor!(cursor, selected, elements);
+            if let Element::Token(Token {
+                type_: TokenType::NormalOpr(OprType::Lt),
+                ..
+            }) = selected
+            {
+                raw = format!("{}{}", raw, selected.get_raw());
+                let contents = catch_between(
+                    TokenType::NormalOpr(OprType::Lt),
+                    TokenType::NormalOpr(OprType::Gt),
+                    &elements,
+                    &mut cursor,
+                )?;
+                raw = format!(
+                    "{}{}{}",
+                    raw,
+                    contents.get_raw(),
+                    elements[cursor].get_raw()
+                );
+                check_and_update_cursor!(cursor, selected, elements);
+            }
+            if !matches!(
+                selected,
+                Element::Token(Token {
+                    type_: TokenType::Keyword(Keyword::Proc | Keyword::Fn) | TokenType::Bar,
+                    ..
+                })
+            ) {
+                return Err(ZyxtError::error_2_1_0(selected.get_raw()).with_element(selected));
+            }
+            infix_raw = Some(raw);
+        }
         if let Element::Token(Token { type_, .. }) = selected {
             if [
                 TokenType::Keyword(Keyword::Proc),
@@ -600,13 +1400,26 @@ fn parse_procs_and_fns(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError
                 } else {
                     false
                 };
-                let mut raw = selected.get_raw().to_owned();
+                let is_infix = infix_raw.is_some();
+                let mut raw = format!("{}{}", infix_raw.unwrap_or_default(), selected.get_raw());
+                let mut label = None;
+                let mut generics = vec![];
                 if type_ != &TokenType::Bar {
+                    label = get_label(&mut cursor, &elements, &mut raw)?;
                     check_and_update_cursor!(cursor, selected, elements);
                     raw = format!("{}{}", raw, selected.get_raw());
+                    if let Element::Token(Token {
+                        type_: TokenType::NormalOpr(OprType::Lt),
+                        ..
+                    }) = selected
+                    {
+                        generics = get_generics(&mut cursor, &elements, &mut raw)?;
+                        check_and_update_cursor!(cursor, selected, elements);
+                        raw = format!("{}{}", raw, selected.get_raw());
+                    }
                 }
 
-                let args = if let Element::Token(Token {
+                let mut args = if let Element::Token(Token {
                     type_: TokenType::Bar,
                     ..
                 }) = selected
@@ -616,56 +1429,105 @@ fn parse_procs_and_fns(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError
                     cursor -= 1;
                     vec![]
                 };
+                for arg in args.iter_mut() {
+                    arg.type_ = arg.type_.to_owned().with_generics(&generics);
+                }
+                if is_infix && args.len() < 2 {
+                    return Err(
+                        ZyxtError::error_2_1_27(args.len()).with_pos_and_raw(&position, &raw)
+                    );
+                }
 
                 check_and_update_cursor!(cursor, selected, elements);
                 let return_type = if let Element::Token(Token {
                     type_: TokenType::Colon,
-                    value,
                     ..
                 }) = selected
                 {
-                    let mut catcher = vec![];
-                    raw = format!("{}{}", raw, value);
-                    loop {
-                        check_and_update_cursor!(cursor, selected, elements);
-                        raw = format!("{}{}", raw, selected.get_raw());
-                        if let Element::Block { .. } = selected {
-                            break;
-                        }
-                        catcher.push(selected.to_owned());
-                    }
-                    if let Element::Variable { name, .. } = parse_expr(catcher)? {
-                        Type::Instance {
-                            name,
-                            type_args: vec![],
-                            inst_attrs: Default::default(),
-                            implementation: None,
-                        }
-                    } else {
-                        todo!("throw error here")
-                    }
+                    let return_type = get_return_type(&mut cursor, &elements, &mut raw)?;
+                    selected = &elements[cursor];
+                    return_type.with_generics(&generics)
                 } else {
                     Type::null()
                 };
 
                 if let Element::Block { content, .. } = selected {
-                    new_elements.push(Element::Procedure {
-                        position,
+                    let mut procs = vec![Element::Procedure {
+                        position: position.to_owned(),
                         is_fn,
+                        generics: generics.to_owned(),
                         args,
                         return_type,
-                        raw,
+                        raw: raw.to_owned(),
                         content: content.to_owned(),
+                        label: label.to_owned(),
+                    }];
+                    // each further set of arguments and body overloads the procedure
+                    while let Some(Element::Token(Token {
+                        type_: TokenType::Bar,
+                        ..
+                    })) = elements.get(cursor + 1)
+                    {
+                        cursor += 1;
+                        selected = &elements[cursor];
+                        let overload_position = selected.get_pos().to_owned();
+                        let mut overload_raw = selected.get_raw();
+                        let mut args = get_arguments(&mut cursor, &elements, &mut overload_raw)?;
+                        for arg in args.iter_mut() {
+                            arg.type_ = arg.type_.to_owned().with_generics(&generics);
+                        }
+                        check_and_update_cursor!(cursor, selected, elements);
+                        let return_type = if let Element::Token(Token {
+                            type_: TokenType::Colon,
+                            ..
+                        }) = selected
+                        {
+                            let return_type =
+                                get_return_type(&mut cursor, &elements, &mut overload_raw)?;
+                            selected = &elements[cursor];
+                            return_type.with_generics(&generics)
+                        } else {
+                            overload_raw = format!("{}{}", overload_raw, selected.get_raw());
+                            Type::null()
+                        };
+                        if let Element::Block { content, .. } = selected {
+                            procs.push(Element::Procedure {
+                                position: overload_position,
+                                is_fn,
+                                generics: generics.to_owned(),
+                                args,
+                                return_type,
+                                raw: overload_raw.to_owned(),
+                                content: content.to_owned(),
+                                label: label.to_owned(),
+                            });
+                            raw = format!("{}{}", raw, overload_raw);
+                        } else {
+                            return Err(
+                                ZyxtError::error_2_1_0(selected.get_raw()).with_element(selected)
+                            );
+                        }
+                    }
+                    new_elements.push(if procs.len() == 1 {
+                        procs.remove(0)
+                    } else {
+                        Element::Overload {
+                            position,
+                            raw,
+                            procs,
+                        }
                     });
                 } else {
                     let content = parse_expr(elements[cursor..].to_vec())?;
                     new_elements.push(Element::Procedure {
                         position,
                         is_fn,
+                        generics,
                         args,
                         return_type,
                         raw: format!("{}{}", raw, content.get_raw()),
                         content: vec![content],
+                        label,
                     });
                     return Ok(new_elements);
                 }
@@ -760,17 +1622,20 @@ fn parse_un_oprs(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
                 if i == 0 {
                     return Err(ZyxtError::error_2_1_4(ele.get_raw()).with_element(ele));
                 }
-                let operand = parse_un_oprs(elements[..i].to_vec())?[0].to_owned();
+                // right unary operators bind tighter than anything else, so the operand is the element before
+                let operand = elements[i - 1].to_owned();
                 return parse_un_oprs(
-                    vec![Element::UnaryOpr {
-                        position: position.to_owned(),
-                        type_: *opr_type,
-                        raw: format!("{}{}", operand.get_raw(), ele.get_raw()),
-                        operand: Box::new(operand),
-                    }]
-                    .into_iter()
-                    .chain(elements[i + 1..].to_vec())
-                    .collect::<Vec<Element>>(),
+                    elements[..i - 1]
+                        .iter()
+                        .cloned()
+                        .chain(vec![Element::UnaryOpr {
+                            position: position.to_owned(),
+                            type_: *opr_type,
+                            raw: format!("{}{}", operand.get_raw(), ele.get_raw()),
+                            operand: Box::new(operand),
+                        }])
+                        .chain(elements[i + 1..].to_vec())
+                        .collect::<Vec<Element>>(),
                 );
             }
         }
@@ -786,24 +1651,74 @@ fn parse_normal_oprs(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError>
     let mut highest_order = 0;
     let mut opr_detected = false;
     for (i, ele) in elements.iter().enumerate() {
-        if let Element::Token(Token {
-            type_: TokenType::NormalOpr(opr_type),
-            value,
-            ..
-        }) = ele
-        {
-            if i == 0 || i == elements.len() - 1 {
-                return Err(ZyxtError::error_2_1_3(value.to_owned()).with_element(ele));
+        // infix procedures were given their order by `mark_infix_procs`
+        let (order, associativity) = match ele {
+            Element::Token(Token {
+                type_: TokenType::NormalOpr(opr_type),
+                value,
+                ..
+            }) => {
+                if i == 0 || i == elements.len() - 1 {
+                    return Err(ZyxtError::error_2_1_3(value.to_owned()).with_element(ele));
+                }
+                let associativity = if *opr_type == OprType::Power {
+                    Associativity::Right
+                } else {
+                    Associativity::Left
+                };
+                (get_order(opr_type), associativity)
             }
-            if get_order(opr_type) >= highest_order {
-                highest_order_index = i;
-                highest_order = get_order(opr_type);
-                opr_detected = true
+            Element::Token(Token {
+                type_: TokenType::InfixProc(order, associativity),
+                value,
+                ..
+            }) => {
+                if i == 0 || i == elements.len() - 1 {
+                    return Err(ZyxtError::error_2_1_3(value.to_owned()).with_element(ele));
+                }
+                (*order, *associativity)
             }
+            _ => continue,
+        };
+        // the last operator of a left-associative chain is applied last, and the first of a right-associative one
+        if !opr_detected
+            || order > highest_order
+            || (order == highest_order && associativity == Associativity::Left)
+        {
+            highest_order_index = i;
+            highest_order = order;
+            opr_detected = true
         }
     }
     Ok(if !opr_detected {
         elements
+    } else if let Element::Token(
+        infix @ Token {
+            type_: TokenType::InfixProc(..),
+            position,
+            ..
+        },
+    ) = &elements[highest_order_index]
+    {
+        let operand1 = parse_expr(elements[..highest_order_index].to_vec())?;
+        let operand2 = parse_expr(elements[highest_order_index + 1..].to_vec())?;
+        vec![Element::Call {
+            position: position.to_owned(),
+            raw: format!(
+                "{}{}{}",
+                operand1.get_raw(),
+                infix.get_raw(),
+                operand2.get_raw()
+            ),
+            called: Box::new(Element::Variable {
+                position: position.to_owned(),
+                name: infix.value.to_owned(),
+                raw: infix.get_raw(),
+                parent: Box::new(Element::NullElement),
+            }),
+            args: vec![operand1, operand2],
+            kwargs: HashMap::new(),
+        }]
     } else if let Element::Token(Token {
         type_: TokenType::NormalOpr(opr_type),
         position,
@@ -883,19 +1798,20 @@ fn parse_delete_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError>
 fn parse_return_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
     let mut new_elements = vec![];
 
-    for (i, ele) in elements.iter().enumerate() {
+    for (mut i, ele) in elements.iter().enumerate() {
         if let Element::Token(Token {
             type_: TokenType::Keyword(Keyword::Return),
-            whitespace,
-            value,
             ..
         }) = ele
         {
+            let mut raw = ele.get_raw();
+            let label = get_label(&mut i, &elements, &mut raw)?;
             let return_val = parse_expr(elements[i + 1..].to_vec())?;
             new_elements.push(Element::Return {
                 position: ele.get_pos().to_owned(),
-                raw: format!("{}{}{}", whitespace, value, return_val.get_raw()),
+                raw: format!("{}{}", raw, return_val.get_raw()),
                 value: Box::new(return_val),
+                label,
             });
             return Ok(new_elements);
         }
@@ -904,6 +1820,42 @@ fn parse_return_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError>
     Ok(elements)
 }
 
+fn parse_break_and_continue_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
+    let mut cursor = 0;
+    let mut selected;
+    let mut new_elements: Vec<Element> = vec![];
+
+    while cursor < elements.len() {
+        selected = &elements[cursor];
+        if let Element::Token(Token {
+            type_: TokenType::Keyword(kwd @ (Keyword::Break | Keyword::Continue)),
+            position,
+            ..
+        }) = selected
+        {
+            let mut raw = selected.get_raw();
+            let label = get_label(&mut cursor, &elements, &mut raw)?;
+            new_elements.push(if *kwd == Keyword::Break {
+                Element::Break {
+                    position: position.to_owned(),
+                    raw,
+                    label,
+                }
+            } else {
+                Element::Continue {
+                    position: position.to_owned(),
+                    raw,
+                    label,
+                }
+            });
+        } else {
+            new_elements.push(selected.to_owned());
+        }
+        cursor += 1;
+    }
+    Ok(new_elements)
+}
+
 fn parse_declaration_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
     let mut cursor = 0;
     let mut selected;
@@ -917,7 +1869,18 @@ fn parse_declaration_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtEr
             ..
         }) = selected
         {
-            flag_pos = Some(cursor);
+            // a declaration's flags start at the first of a run of them, eg `const priv`
+            if cursor == 0
+                || !matches!(
+                    elements[cursor - 1],
+                    Element::Token(Token {
+                        type_: TokenType::Flag(_),
+                        ..
+                    })
+                )
+            {
+                flag_pos = Some(cursor);
+            }
         }
         if let Element::Token(Token {
             type_: TokenType::DeclarationOpr,
@@ -930,13 +1893,42 @@ fn parse_declaration_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtEr
             if cursor == elements.len() - 1 || cursor == 0 {
                 return Err(ZyxtError::error_2_1_5().with_element(selected));
             }
-            let declared_var: &Element = &elements[cursor - 1];
-            let mut raw = format!("{}{}{}", declared_var.get_raw(), whitespace, value);
+            // the type of the variable, if declared, is between a colon and the `:=`
+            let colon_pos = elements[..cursor].iter().rposition(|ele| {
+                matches!(
+                    ele,
+                    Element::Token(Token {
+                        type_: TokenType::Colon,
+                        ..
+                    })
+                )
+            });
+            let (var_pos, type_) = match colon_pos {
+                Some(colon_pos) if colon_pos > 0 && colon_pos < cursor - 1 => (
+                    colon_pos - 1,
+                    parse_type(elements[colon_pos + 1..cursor].to_vec())?,
+                ),
+                Some(colon_pos) => {
+                    return Err(ZyxtError::error_2_1_5().with_element(&elements[colon_pos]))
+                }
+                None => (cursor - 1, Type::null()),
+            };
+            let declared_var: &Element = &elements[var_pos];
+            let mut raw = format!(
+                "{}{}{}",
+                elements[var_pos..cursor]
+                    .iter()
+                    .map(|e| e.get_raw())
+                    .collect::<Vec<String>>()
+                    .join(""),
+                whitespace,
+                value
+            );
             let flags = if flag_pos == None {
                 vec![]
             } else {
                 let mut f = vec![];
-                for ele in elements[flag_pos.unwrap()..cursor - 1].iter() {
+                for ele in elements[flag_pos.unwrap()..var_pos].iter().rev() {
                     if let Element::Token(Token {
                         type_: TokenType::Flag(flag),
                         whitespace,
@@ -945,14 +1937,14 @@ fn parse_declaration_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtEr
                     }) = &ele
                     {
                         raw = format!("{}{}{}", whitespace, value, raw);
-                        f.push(*flag);
+                        f.insert(0, *flag);
                     } else {
                         return Err(ZyxtError::error_2_1_6(ele.get_raw()).with_element(ele));
                     }
                 }
                 f
             };
-            for _ in 0..flags.len() + 1 {
+            for _ in 0..flags.len() + cursor - var_pos {
                 new_elements.pop();
             }
             let content = parse_expr(elements[cursor + 1..].to_vec())?;
@@ -962,7 +1954,7 @@ fn parse_declaration_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtEr
                 variable: Box::new(parse_expr(vec![declared_var.to_owned()])?),
                 content: Box::new(content),
                 flags,
-                type_: Type::null(), // TODO type later
+                type_,
             });
             break;
         } else {
@@ -1086,6 +2078,466 @@ pub fn parse_if_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError>
     Ok(new_elements)
 }
 
+fn parse_pattern(elements: Vec<Element>) -> Result<Pattern, ZyxtError> {
+    if let [Element::Token(Token {
+        type_: TokenType::Variable,
+        value,
+        ..
+    })] = &elements[..]
+    {
+        if value == "_" {
+            return Ok(Pattern::Wildcard);
+        }
+    }
+    // `Type(field, field: pattern)`
+    if let Some(Element::Token(Token {
+        type_: TokenType::CloseParen,
+        ..
+    })) = elements.last()
+    {
+        let mut cursor = elements
+            .iter()
+            .position(|ele| {
+                matches!(
+                    ele,
+                    Element::Token(Token {
+                        type_: TokenType::OpenParen,
+                        ..
+                    })
+                )
+            })
+            .unwrap_or(0);
+        if cursor != 0 {
+            let type_ = parse_expr(elements[..cursor].to_vec())?;
+            let contents = catch_between(
+                TokenType::OpenParen,
+                TokenType::CloseParen,
+                &elements,
+                &mut cursor,
+            )?;
+            if cursor == elements.len() - 1 {
+                let fields = base_split(
+                    &|field| {
+                        let colon_pos = field.iter().position(|ele| {
+                            matches!(
+                                ele,
+                                Element::Token(Token {
+                                    type_: TokenType::Colon,
+                                    ..
+                                })
+                            )
+                        });
+                        let name_elements = field[..colon_pos.unwrap_or(field.len())].to_vec();
+                        let name = if let Element::Variable { name, .. } =
+                            parse_expr(name_elements.to_owned())?
+                        {
+                            name
+                        } else {
+                            return Err(ZyxtError::error_2_1_15(name_elements.get_raw())
+                                .with_element(&field[0]));
+                        };
+                        let pattern = if let Some(colon_pos) = colon_pos {
+                            parse_pattern(field[colon_pos + 1..].to_vec())?
+                        } else {
+                            Pattern::Binding(name.to_owned())
+                        };
+                        Ok((name, pattern))
+                    },
+                    None,
+                    TokenType::Comma,
+                    TokenType::OpenParen,
+                    TokenType::CloseParen,
+                    contents,
+                    false,
+                )?;
+                return Ok(Pattern::Destructure { type_, fields });
+            }
+        }
+    }
+    Ok(Pattern::Value(parse_expr(elements)?))
+}
+
+fn parse_match_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
+    let mut cursor = 0;
+    let mut selected;
+    let mut new_elements: Vec<Element> = vec![];
+
+    while cursor < elements.len() {
+        selected = &elements[cursor];
+        if let Element::Token(Token {
+            type_: TokenType::Keyword(kwd),
+            position,
+            ..
+        }) = selected
+        {
+            match kwd {
+                Keyword::Match => {
+                    let mut raw = selected.get_raw();
+                    let mut catcher = vec![];
+                    loop {
+                        if cursor == elements.len() - 1 {
+                            return Err(ZyxtError::error_2_1_21().with_pos_and_raw(position, &raw));
+                        }
+                        cursor += 1;
+                        selected = &elements[cursor];
+                        if let Element::Token(Token {
+                            type_: TokenType::Keyword(Keyword::Of),
+                            ..
+                        }) = selected
+                        {
+                            break;
+                        }
+                        raw = format!("{}{}", raw, selected.get_raw());
+                        catcher.push(selected.to_owned());
+                    }
+                    let subject = parse_expr(catcher)?;
+
+                    let mut arms = vec![];
+                    loop {
+                        let is_else = matches!(
+                            selected,
+                            Element::Token(Token {
+                                type_: TokenType::Keyword(Keyword::Else),
+                                ..
+                            })
+                        );
+                        let arm_position = selected.get_pos().to_owned();
+                        let mut arm_raw = selected.get_raw();
+                        let mut catcher = vec![];
+                        loop {
+                            check_and_update_cursor!(cursor, selected, elements);
+                            if let Element::Block { .. } = selected {
+                                break;
+                            }
+                            arm_raw = format!("{}{}", arm_raw, selected.get_raw());
+                            catcher.push(selected.to_owned());
+                        }
+                        if is_else && !catcher.is_empty() {
+                            return Err(ZyxtError::error_2_1_8(catcher.get_raw())
+                                .with_element(&catcher[0]));
+                        } else if !is_else && catcher.is_empty() {
+                            return Err(
+                                ZyxtError::error_2_2(selected.to_owned()).with_element(selected)
+                            );
+                        }
+                        let pattern = if is_else {
+                            Pattern::Wildcard
+                        } else {
+                            parse_pattern(catcher)?
+                        };
+                        if let Element::Block {
+                            content,
+                            raw: block_raw,
+                            ..
+                        } = selected
+                        {
+                            raw = format!("{}{}{}", raw, arm_raw, block_raw);
+                            arms.push(MatchArm {
+                                position: arm_position,
+                                raw: arm_raw,
+                                pattern,
+                                content: content.to_owned(),
+                            });
+                        }
+
+                        if let Some(Element::Token(Token {
+                            type_: TokenType::Keyword(next_kwd @ (Keyword::Of | Keyword::Else)),
+                            ..
+                        })) = elements.get(cursor + 1)
+                        {
+                            if is_else {
+                                return Err(ZyxtError::error_2_1_7(
+                                    if *next_kwd == Keyword::Of { "of" } else { "else" }
+                                        .to_string(),
+                                )
+                                .with_element(&elements[cursor + 1]));
+                            }
+                            cursor += 1;
+                            selected = &elements[cursor];
+                        } else {
+                            break;
+                        }
+                    }
+                    new_elements.push(Element::Match {
+                        position: position.to_owned(),
+                        raw,
+                        subject: Box::new(subject),
+                        arms,
+                    });
+                }
+                Keyword::Of => {
+                    return Err(ZyxtError::error_2_1_22().with_element(selected));
+                }
+                _ => new_elements.push(selected.to_owned()),
+            }
+        } else {
+            new_elements.push(selected.to_owned());
+        }
+        cursor += 1;
+    }
+    Ok(new_elements)
+}
+
+fn parse_while_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
+    let mut cursor = 0;
+    let mut selected;
+    let mut new_elements: Vec<Element> = vec![];
+
+    while cursor < elements.len() {
+        selected = &elements[cursor];
+        if let Element::Token(Token {
+            type_: TokenType::Keyword(kwd),
+            position,
+            ..
+        }) = selected
+        {
+            match kwd {
+                Keyword::While => {
+                    let mut raw = selected.get_raw();
+                    let label = get_label(&mut cursor, &elements, &mut raw)?;
+                    check_and_update_cursor!(cursor, selected, elements);
+                    let condition = if let Element::Block { raw: block_raw, .. } = selected {
+                        raw = format!("{}{}", raw, block_raw);
+                        let condition = selected.to_owned();
+                        check_and_update_cursor!(cursor, selected, elements);
+                        condition
+                    } else {
+                        let mut catcher = vec![selected.to_owned()];
+                        raw = format!("{}{}", raw, selected.get_raw());
+                        loop {
+                            check_and_update_cursor!(cursor, selected, elements);
+                            if let Element::Block { .. } = selected {
+                                break;
+                            }
+                            raw = format!("{}{}", raw, selected.get_raw());
+                            catcher.push(selected.to_owned());
+                        }
+                        parse_expr(catcher)?
+                    };
+                    if let Element::Block {
+                        content,
+                        raw: block_raw,
+                        ..
+                    } = selected
+                    {
+                        new_elements.push(Element::While {
+                            position: position.to_owned(),
+                            raw: format!("{}{}", raw, block_raw),
+                            condition: Box::new(condition),
+                            content: content.to_owned(),
+                            do_first: false,
+                            label,
+                        });
+                    } else {
+                        return Err(
+                            ZyxtError::error_2_1_8(selected.get_raw()).with_element(selected)
+                        );
+                    }
+                }
+                Keyword::Do => {
+                    let mut raw = selected.get_raw();
+                    let label = get_label(&mut cursor, &elements, &mut raw)?;
+                    check_and_update_cursor!(cursor, selected, elements);
+                    let content = if let Element::Block {
+                        content,
+                        raw: block_raw,
+                        ..
+                    } = selected
+                    {
+                        raw = format!("{}{}", raw, block_raw);
+                        content.to_owned()
+                    } else {
+                        return Err(
+                            ZyxtError::error_2_1_8(selected.get_raw()).with_element(selected)
+                        );
+                    };
+                    if cursor == elements.len() - 1 {
+                        return Err(ZyxtError::error_2_1_19().with_pos_and_raw(position, &raw));
+                    }
+                    check_and_update_cursor!(cursor, selected, elements);
+                    if let Element::Token(Token {
+                        type_: TokenType::Keyword(Keyword::While),
+                        ..
+                    }) = selected
+                    {
+                        raw = format!("{}{}", raw, selected.get_raw());
+                    } else {
+                        return Err(ZyxtError::error_2_1_19().with_element(selected));
+                    }
+                    if cursor == elements.len() - 1 {
+                        return Err(
+                            ZyxtError::error_2_1_0(selected.get_raw()).with_element(selected)
+                        );
+                    }
+                    let condition = parse_expr(elements[cursor + 1..].to_vec())?;
+                    new_elements.push(Element::While {
+                        position: position.to_owned(),
+                        raw: format!("{}{}", raw, condition.get_raw()),
+                        condition: Box::new(condition),
+                        content,
+                        do_first: true,
+                        label,
+                    });
+                    break;
+                }
+                Keyword::Loop => {
+                    let mut raw = selected.get_raw();
+                    let label = get_label(&mut cursor, &elements, &mut raw)?;
+                    check_and_update_cursor!(cursor, selected, elements);
+                    if let Element::Block {
+                        content,
+                        raw: block_raw,
+                        ..
+                    } = selected
+                    {
+                        new_elements.push(Element::While {
+                            position: position.to_owned(),
+                            raw: format!("{}{}", raw, block_raw),
+                            condition: Box::new(Element::NullElement),
+                            content: content.to_owned(),
+                            do_first: false,
+                            label,
+                        });
+                    } else {
+                        return Err(
+                            ZyxtError::error_2_1_8(selected.get_raw()).with_element(selected)
+                        );
+                    }
+                }
+                _ => new_elements.push(selected.to_owned()),
+            }
+        } else {
+            new_elements.push(selected.to_owned());
+        }
+        cursor += 1;
+    }
+    Ok(new_elements)
+}
+
+fn parse_for_expr(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
+    let mut cursor = 0;
+    let mut selected;
+    let mut new_elements: Vec<Element> = vec![];
+
+    while cursor < elements.len() {
+        selected = &elements[cursor];
+        if let Element::Token(Token {
+            type_: TokenType::Keyword(Keyword::For),
+            position,
+            ..
+        }) = selected
+        {
+            let mut raw = selected.get_raw();
+            let label = get_label(&mut cursor, &elements, &mut raw)?;
+            check_and_update_cursor!(cursor, selected, elements);
+            if let Element::Block {
+                content: header,
+                raw: header_raw,
+                ..
+            } = selected
+            {
+                let mut header = header.to_owned();
+                if header.len() == 4 && header[3] == Element::NullElement {
+                    header.pop();
+                }
+                if header.len() != 3 {
+                    return Err(ZyxtError::error_2_1_20().with_element(selected));
+                }
+                raw = format!("{}{}", raw, header_raw);
+                check_and_update_cursor!(cursor, selected, elements);
+                if let Element::Block {
+                    content,
+                    raw: block_raw,
+                    ..
+                } = selected
+                {
+                    let step = header.pop().unwrap();
+                    let condition = header.pop().unwrap();
+                    let init = header.pop().unwrap();
+                    new_elements.push(Element::CStyleFor {
+                        position: position.to_owned(),
+                        raw: format!("{}{}", raw, block_raw),
+                        init: Box::new(init),
+                        condition: Box::new(condition),
+                        step: Box::new(step),
+                        content: content.to_owned(),
+                        label,
+                    });
+                } else {
+                    return Err(ZyxtError::error_2_1_8(selected.get_raw()).with_element(selected));
+                }
+            } else {
+                let mut binding = vec![];
+                while !matches!(
+                    selected,
+                    Element::Token(Token {
+                        type_: TokenType::Keyword(Keyword::In),
+                        ..
+                    })
+                ) {
+                    raw = format!("{}{}", raw, selected.get_raw());
+                    binding.push(selected.to_owned());
+                    check_and_update_cursor!(cursor, selected, elements);
+                }
+                raw = format!("{}{}", raw, selected.get_raw());
+                check_and_update_cursor!(cursor, selected, elements);
+                let mut catcher = vec![];
+                while catcher.is_empty() || !matches!(selected, Element::Block { .. }) {
+                    raw = format!("{}{}", raw, selected.get_raw());
+                    catcher.push(selected.to_owned());
+                    check_and_update_cursor!(cursor, selected, elements);
+                }
+                let iterable = parse_expr(catcher)?;
+
+                let parts = split_between(
+                    TokenType::Colon,
+                    TokenType::Null,
+                    TokenType::Null,
+                    binding.to_owned(),
+                    true,
+                )?;
+                let variable = if let Some(variable @ Element::Variable { .. }) = parts.first() {
+                    variable.to_owned()
+                } else {
+                    return Err(ZyxtError::error_2_1_15(binding.get_raw())
+                        .with_pos_and_raw(position, &raw));
+                };
+                if parts.len() > 2 {
+                    return Err(ZyxtError::error_2_1_0(parts[2].get_raw()).with_element(&parts[2]));
+                }
+                let type_ = if let Some(type_) = parts.get(1) {
+                    type_.as_type()
+                } else {
+                    Type::null()
+                };
+
+                if let Element::Block {
+                    content,
+                    raw: block_raw,
+                    ..
+                } = selected
+                {
+                    new_elements.push(Element::For {
+                        position: position.to_owned(),
+                        raw: format!("{}{}", raw, block_raw),
+                        variable: Box::new(variable),
+                        type_,
+                        iterable: Box::new(iterable),
+                        content: content.to_owned(),
+                        label,
+                    });
+                } else {
+                    unreachable!()
+                }
+            }
+        } else {
+            new_elements.push(selected.to_owned());
+        }
+        cursor += 1;
+    }
+    Ok(new_elements)
+}
+
 fn parse_unparen_calls(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
     let comma_pos = elements
         .iter()
@@ -1115,15 +2567,13 @@ fn parse_unparen_calls(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError
             )
         });
     if let Some(right_un_pos) = right_un_pos {
-        if right_un_pos + 1 != comma_pos {
-            let min_index = min(right_un_pos + 1, elements.len());
-            return parse_unparen_calls(
-                parse_un_oprs(elements[..min_index].to_vec())?
-                    .into_iter()
-                    .chain(elements[min_index..].iter().cloned())
-                    .collect(),
-            );
-        }
+        let min_index = min(right_un_pos + 1, elements.len());
+        return parse_unparen_calls(
+            parse_un_oprs(elements[..min_index].to_vec())?
+                .into_iter()
+                .chain(elements[min_index..].iter().cloned())
+                .collect(),
+        );
     }
     let left_un_pos = elements
         .iter()
@@ -1155,6 +2605,7 @@ fn parse_unparen_calls(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError
     if elements.len() == 1 {
         return Ok(elements);
     }
+    let (args, kwargs) = get_call_args(TokenType::Null, TokenType::Null, elements[1..].to_vec())?;
     Ok(vec![Element::Call {
         position: elements[0].get_pos().to_owned(),
         raw: elements
@@ -1163,14 +2614,8 @@ fn parse_unparen_calls(elements: Vec<Element>) -> Result<Vec<Element>, ZyxtError
             .collect::<Vec<String>>()
             .join(""),
         called: Box::new(elements[0].to_owned()),
-        args: split_between(
-            TokenType::Comma,
-            TokenType::Null,
-            TokenType::Null,
-            elements[1..].to_vec(),
-            false,
-        )?,
-        kwargs: Default::default(),
+        args,
+        kwargs,
     }])
 }
 
@@ -1178,14 +2623,19 @@ fn parse_expr(mut elements: Vec<Element>) -> Result<Element, ZyxtError> {
     if elements.len() > 1 {
         elements = parse_parens(elements)?;
     }
+    elements = parse_match_expr(elements)?;
     elements = parse_if_expr(elements)?;
+    elements = parse_while_expr(elements)?;
+    elements = parse_for_expr(elements)?;
+    elements = parse_break_and_continue_expr(elements)?;
+    elements = parse_return_expr(elements)?;
+    // struct fields are between bars, so they are parsed before procs take the bars
+    elements = parse_classes_structs_and_mixins(elements)?;
     elements = parse_procs_and_fns(elements)?;
     elements = parse_preprocess_and_defer(elements)?;
-    elements = parse_classes_structs_and_mixins(elements)?;
-    //elements = parse_enums(elements)?;
+    elements = parse_enums(elements)?;
     elements = parse_vars_literals_and_calls(elements)?;
     elements = parse_delete_expr(elements)?;
-    elements = parse_return_expr(elements)?;
     elements = parse_declaration_expr(elements)?;
     elements = parse_assignment_oprs(elements)?;
     elements = parse_normal_oprs(elements)?;
@@ -1209,6 +2659,155 @@ fn parse_block(input: Vec<Element>) -> Result<Vec<Element>, ZyxtError> {
     )
 }
 
+/// The indices of the curly brackets around the block containing the token at `i`,
+/// or the ends of the tokens if it is not in one
+fn enclosing_block(tokens: &[Token], i: usize) -> (usize, usize) {
+    let mut depth = 0;
+    let start = tokens[..i]
+        .iter()
+        .rposition(|token| match token.type_ {
+            TokenType::CloseCurlyParen => {
+                depth += 1;
+                false
+            }
+            TokenType::OpenCurlyParen if depth == 0 => true,
+            TokenType::OpenCurlyParen => {
+                depth -= 1;
+                false
+            }
+            _ => false,
+        })
+        .unwrap_or(0);
+    let mut depth = 0;
+    let end = tokens[i..]
+        .iter()
+        .position(|token| match token.type_ {
+            TokenType::OpenCurlyParen => {
+                depth += 1;
+                false
+            }
+            TokenType::CloseCurlyParen if depth == 0 => true,
+            TokenType::CloseCurlyParen => {
+                depth -= 1;
+                false
+            }
+            _ => false,
+        })
+        .map_or(tokens.len(), |end| i + end);
+    (start, end)
+}
+
+/// Marks the variables used infix where an infix procedure of their name is declared,
+/// which is anywhere in the block containing the declaration, even before it
+fn mark_infix_procs(tokens: &mut [Token]) -> Result<(), ZyxtError> {
+    let mut declared = vec![];
+    for (i, token) in tokens.iter().enumerate() {
+        if token.type_ != TokenType::Keyword(Keyword::Infix) {
+            continue;
+        }
+        let name = match tokens.get(i.wrapping_sub(2)..i) {
+            Some(
+                [Token {
+                    type_: TokenType::Variable,
+                    value,
+                    ..
+                }, Token {
+                    type_: TokenType::DeclarationOpr,
+                    ..
+                }],
+            ) => value,
+            _ => return Err(ZyxtError::error_2_1_26().with_token(token)),
+        };
+        let mut order = (DEFAULT_INFIX_ORDER, Associativity::Left);
+        if tokens
+            .get(i + 1)
+            .is_some_and(|token| token.type_ == TokenType::NormalOpr(OprType::Lt))
+        {
+            let end = tokens[i + 1..]
+                .iter()
+                .position(|token| token.type_ == TokenType::NormalOpr(OprType::Gt))
+                .map(|end| i + 1 + end)
+                .unwrap_or(tokens.len());
+            let spec = &tokens[i + 2..end];
+            let spec_raw = spec.iter().map(|token| token.get_raw()).collect::<String>();
+            let associativity = match spec.get(1..) {
+                Some([]) => Some(Associativity::Left),
+                Some(
+                    [Token {
+                        type_: TokenType::Comma,
+                        ..
+                    }, Token {
+                        type_: TokenType::Variable,
+                        value,
+                        ..
+                    }],
+                ) => match &**value {
+                    "left" => Some(Associativity::Left),
+                    "right" => Some(Associativity::Right),
+                    _ => None,
+                },
+                _ => None,
+            };
+            order = match (spec.first(), associativity) {
+                (
+                    Some(Token {
+                        type_: TokenType::LiteralNumber,
+                        value,
+                        ..
+                    }),
+                    Some(associativity),
+                ) if value.parse::<u8>().is_ok() => (value.parse().unwrap(), associativity),
+                _ => {
+                    return Err(ZyxtError::error_2_1_25(spec_raw.trim().to_string())
+                        .with_pos_and_raw(&token.position, &spec_raw))
+                }
+            };
+        }
+        declared.push((i - 2, name.to_owned(), order, enclosing_block(tokens, i)));
+    }
+    for i in 1..tokens.len().saturating_sub(1) {
+        if tokens[i].type_ != TokenType::Variable {
+            continue;
+        }
+        // only a variable between two operands is used infix
+        let after_operand = matches!(
+            tokens[i - 1].type_,
+            TokenType::Variable
+                | TokenType::LiteralNumber
+                | TokenType::LiteralMisc
+                | TokenType::LiteralString
+                | TokenType::CloseParen
+                | TokenType::CloseSquareParen
+                | TokenType::UnaryOpr(_, Side::Right)
+        );
+        let before_operand = matches!(
+            tokens[i + 1].type_,
+            TokenType::Variable
+                | TokenType::LiteralNumber
+                | TokenType::LiteralMisc
+                | TokenType::LiteralString
+                | TokenType::OpenParen
+                | TokenType::OpenSquareParen
+                | TokenType::UnaryOpr(_, Side::Left)
+        );
+        if !after_operand || !before_operand {
+            continue;
+        }
+        // the declaration in the innermost block containing the variable is used
+        if let Some((_, _, (order, associativity), _)) = declared
+            .iter()
+            .filter(|(declared_at, name, _, (start, end))| {
+                *declared_at != i && *name == tokens[i].value && (*start..*end).contains(&i)
+            })
+            .min_by_key(|(_, _, _, (start, end))| end - start)
+        {
+            tokens[i].type_ = TokenType::InfixProc(*order, *associativity);
+            tokens[i].categories = &[TokenCategory::Operator];
+        }
+    }
+    Ok(())
+}
+
 pub fn parse_token_list(mut input: Vec<Token>) -> Result<Vec<Element>, ZyxtError> {
     let mut comments: Vec<Element> = vec![];
 
@@ -1236,6 +2835,7 @@ pub fn parse_token_list(mut input: Vec<Token>) -> Result<Vec<Element>, ZyxtError
         .into_iter()
         .filter(|token| token.type_ != TokenType::Comment)
         .collect();
+    mark_infix_procs(&mut input)?;
 
     // generate and return an AST for each expression
     parse_block(
@@ -1,4 +1,5 @@
 use crate::interpreter::interpret_expr;
+use crate::objects::control_flow::ControlFlow;
 use crate::objects::interpreter_data::{InterpreterData, StdIoPrint};
 use crate::objects::value::Value;
 use crate::{compile, Logger, ZyxtError};
@@ -11,12 +12,14 @@ use std::io;
 use std::io::Write;
 use std::time::Instant;
 
-pub fn repl(verbosity: u8) {
+pub fn repl(verbosity: u8, max_call_depth: usize) {
     let filename = "[stdin]".to_string();
     let mut sip1 = StdIoPrint;
     let mut sip2 = StdIoPrint;
     let mut typelist = InterpreterData::default_type(&mut sip1);
     let mut varlist = InterpreterData::default_variable(&mut sip2);
+    typelist.max_call_depth = max_call_depth;
+    varlist.max_call_depth = max_call_depth;
     let mut logger = Logger {
         verbosity,
         out: &mut StdIoPrint
@@ -91,8 +94,11 @@ pub fn repl(verbosity: u8) {
                                 println!("{}{}", out_symbol, Yellow.paint(format!("{:?}", result)))
                             }
                         }
-                        Err(e) => {
-                            e.print(&mut StdIoPrint);
+                        Err(ControlFlow::Return { label: None, value }) => {
+                            println!("{}{}", out_symbol, Yellow.paint(format!("{:?}", value)))
+                        }
+                        Err(flow) => {
+                            flow.into_error().print(&mut StdIoPrint);
                         }
                     }
                 }
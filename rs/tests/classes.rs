@@ -0,0 +1,159 @@
+mod common;
+use common::{lines, run};
+
+const POINT: &str = "PointClass := class {
+    origin := 0;
+    _init := fn |self, a: i32, b: i32| {
+        self.a = a;
+        self.b = b;
+    };
+    sum := fn |self|: i32 {
+        self.a + self.b
+    };
+    shift := proc |self, d: i32| {
+        self.a = self.a + d;
+    };
+};";
+
+#[test]
+fn classes_are_instantiated_with_init() {
+    assert_eq!(
+        lines(&format!(
+            "{} p := PointClass(1, 2); p.shift(3); ter.out(p.a, p.sum(), PointClass.origin);",
+            POINT
+        )),
+        ["4 6 0"]
+    );
+    assert_eq!(
+        run(&format!("{} p := PointClass(1, 2); ter.out(p.c);", POINT)),
+        Err("3.1.0")
+    );
+}
+
+#[test]
+fn classes_inherit_from_parents_and_mixins() {
+    assert_eq!(
+        lines(&format!(
+            "{}
+            Named := mixin {{ greet := fn |self|: str {{ \"Hi, \" ~ self.name }}; }};
+            Point3 := class {{
+                ..PointClass;
+                ..Named;
+                _init := fn |self, a: i32, b: i32, c: i32| {{
+                    PointClass._init(self, a, b);
+                    self.c = c;
+                    self.name = \"point\";
+                }};
+                sum := fn |self|: i32 {{ PointClass.sum(self) + self.c }};
+            }};
+            r := Point3(1, 2, 3);
+            ter.out(r.sum(), r.greet());",
+            POINT
+        )),
+        ["6 Hi, point"]
+    );
+}
+
+#[test]
+fn structs_are_built_from_their_fields_and_compared_by_them() {
+    assert_eq!(
+        lines(
+            "PointStruct := struct |a: i32, b: i32: 0|;
+            q := PointStruct(2, 3);
+            ter.out(q.a, PointStruct(b: 1, a: 4).b, PointStruct(5).b);
+            ter.out(q == PointStruct(2, 3), q == PointStruct(3, 2));"
+        ),
+        ["2 1 0", "true false"]
+    );
+}
+
+#[test]
+fn operators_call_magic_methods() {
+    let vector = "Vector := struct |x: i32, y: i32| {
+        _add := fn |self, o: Vector|: Vector { Vector(self.x + o.x, self.y + o.y) };
+        _mul := fn |self, k: i32|: Vector { Vector(self.x * k, self.y * k) };
+        _rmul := fn |self, k: i32|: Vector { self * k };
+        _neg := fn |self|: Vector { self * -1 };
+        _and := fn |self, o: Vector|: bool { self.x != 0 && o.x != 0 };
+    };";
+    assert_eq!(
+        lines(&format!(
+            "{} v := Vector(1, 2) + Vector(3, 4); ter.out((2 * v).y, (-v).x, v && Vector(0, 0));",
+            vector
+        )),
+        ["12 -4 false"]
+    );
+    assert_eq!(
+        run(&format!("{} ter.out(Vector(1, 2) < Vector(3, 4));", vector)),
+        Err("4.0.0")
+    );
+    assert_eq!(run("ter.out(1 && true);"), Err("4.0.0"));
+}
+
+#[test]
+fn enum_variants_have_no_value_a_value_or_fields() {
+    assert_eq!(
+        lines(
+            "Light := enum {
+                Off;
+                Dim := 1;
+                Bright := 2;
+                Colour := struct |r: u8, g: u8, b: u8|;
+            };
+            l := 2 @ Light;
+            c := Light.Colour(255, 0, 0);
+            ter.out(l @ i32, l == Light.Bright, c == Light.Colour(255, 0, 0), c == Light.Off);
+            ter.out(match c of Light.Colour(r, g, b) { r } else { 0 @ u8 });"
+        ),
+        ["2 true true false", "255"]
+    );
+    assert_eq!(
+        run("Light := enum { Off; }; ter.out(Light.On);"),
+        Err("3.1.2")
+    );
+}
+
+#[test]
+fn const_variables_and_attributes_cannot_be_changed() {
+    assert_eq!(run("const x := 1; x = 2;"), Err("3.4"));
+    assert_eq!(run("const x := 1; x := 2;"), Err("3.4"));
+    assert_eq!(
+        run("C := class { const a := 1; }; c := C(); c.a = 2;"),
+        Err("3.4")
+    );
+    // flags belong to the class they were declared in, not to every class of the same name
+    assert_eq!(
+        lines(
+            "make_a := proc { ret class { x := 1; }; };
+            make_b := proc { ret class { const x := 2; }; };
+            A := make_a(); B := make_b();
+            a := A(); a.x = 5; ter.out(a.x);"
+        ),
+        ["5"]
+    );
+}
+
+#[test]
+fn priv_and_prot_attributes_are_only_used_inside_their_classes() {
+    let classes = "C := class {
+        priv a := 1;
+        prot b := 2;
+        get := fn |self|: i32 { self.a };
+    };
+    D := class {
+        ..C;
+        get_b := fn |self|: i32 { self.b };
+    };";
+    assert_eq!(
+        lines(&format!("{} ter.out(C().get(), D().get_b());", classes)),
+        ["1 2"]
+    );
+    assert_eq!(run(&format!("{} ter.out(C().a);", classes)), Err("3.5.0"));
+    assert_eq!(run(&format!("{} ter.out(D().b);", classes)), Err("3.5.1"));
+    assert_eq!(run("pub priv x := 1;"), Err("3.5.2"));
+}
+
+#[test]
+fn hoisted_variables_are_declared_before_they_are_used() {
+    assert_eq!(lines("ter.out(x); hoi x := 1;"), ["1"]);
+}
@@ -0,0 +1,49 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+use zyxt::objects::interpreter_data::{InterpreterData, Print};
+use zyxt::objects::logger::Logger;
+
+/// Collects what a program prints, one line per `ter.out`
+#[derive(Clone, Default)]
+pub struct Output(Rc<RefCell<String>>);
+impl Print for Output {
+    fn println(&mut self, s: impl Display) {
+        self.0.borrow_mut().push_str(&format!("{}\n", s))
+    }
+    fn print(&mut self, s: impl Display) {
+        self.0.borrow_mut().push_str(&s.to_string())
+    }
+    fn eprintln(&mut self, s: impl Display) {
+        self.println(s)
+    }
+    fn eprint(&mut self, s: impl Display) {
+        self.print(s)
+    }
+}
+
+/// Checks and runs a program, giving what it printed, or the code of the error it stopped with
+pub fn run(program: &str) -> Result<String, &'static str> {
+    let out = Output::default();
+    let (mut type_out, mut value_out, mut log_out) = (out.clone(), out.clone(), out.clone());
+    let mut typelist = InterpreterData::default_type(&mut type_out);
+    let mut i_data = InterpreterData::default_variable(&mut value_out);
+    let mut logger = Logger {
+        verbosity: 0,
+        out: &mut log_out,
+    };
+    let instructions = zyxt::compile(program.to_string(), "test.zy", &mut typelist, &mut logger)
+        .map_err(|e| e.code)?;
+    zyxt::interpret(&instructions, &mut i_data, &mut logger).map_err(|e| e.code)?;
+    let printed = out.0.borrow().to_owned();
+    Ok(printed)
+}
+
+/// The lines a program printed
+pub fn lines(program: &str) -> Vec<String> {
+    run(program)
+        .unwrap_or_else(|code| panic!("error {} in:\n{}", code, program))
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,117 @@
+mod common;
+use common::{lines, run};
+
+#[test]
+fn while_loops_until_the_condition_is_false() {
+    assert_eq!(
+        lines("i := 0; while i < 3 { ter.out(i); i += 1; };"),
+        ["0", "1", "2"]
+    );
+    assert_eq!(lines("while false { ter.out(1); }; ter.out(2);"), ["2"]);
+}
+
+#[test]
+fn do_while_runs_at_least_once() {
+    assert_eq!(
+        lines("i := 5; do { ter.out(i); i += 1; } while i < 3;"),
+        ["5"]
+    );
+    assert_eq!(
+        lines("i := 0; do { i += 1; } while i < 3; ter.out(i);"),
+        ["3"]
+    );
+}
+
+#[test]
+fn for_goes_through_sequences() {
+    assert_eq!(lines("for c in \"ab\" { ter.out(c); };"), ["a", "b"]);
+    assert_eq!(
+        lines("s := 0; for x in [1, 2, 3] { s += x; }; ter.out(s);"),
+        ["6"]
+    );
+    assert_eq!(
+        lines("for x: f64 in [1, 2] { ter.out(x / 2); };"),
+        ["0.5", "1"]
+    );
+}
+
+#[test]
+fn for_checks_its_variable_and_iterable() {
+    assert_eq!(run("for x: f64 in \"ab\" { ter.out(x); };"), Err("4.3"));
+    assert_eq!(run("for x in 1 { ter.out(x); };"), Err("4.5.0"));
+}
+
+#[test]
+fn c_style_for_scopes_its_variable() {
+    assert_eq!(
+        lines("for {j := 0; j < 2; j += 1} { ter.out(j); };"),
+        ["0", "1"]
+    );
+    assert_eq!(
+        run("for {j := 0; j < 2; j += 1} {}; ter.out(j);"),
+        Err("3.0")
+    );
+}
+
+#[test]
+fn labelled_loops_are_broken_out_of_and_continued() {
+    assert_eq!(
+        lines(
+            "n := 0;
+            loop'outer {
+                n += 1;
+                for c in \"xyz\" {
+                    if c == \"y\" { continue'outer; };
+                    if n == 3 { break'outer; };
+                    ter.out(n, c);
+                };
+            };"
+        ),
+        ["1 x", "2 x"]
+    );
+}
+
+#[test]
+fn break_and_continue_need_a_loop() {
+    assert_eq!(run("break;"), Err("2.4"));
+    assert_eq!(run("loop { break'missing; };"), Err("3.2"));
+    // loops can't be broken out of from inside a procedure
+    assert_eq!(run("f := proc { break; }; loop { f(); };"), Err("2.4"));
+}
+
+#[test]
+fn match_checks_each_pattern_in_turn() {
+    let program =
+        "kind := |n: i32|: str { match n of 1 { \"one\" } of 3 { \"three\" } else { \"other\" } };
+        ter.out(kind(1), kind(3), kind(4));";
+    assert_eq!(lines(program), ["one three other"]);
+}
+
+#[test]
+fn match_destructures_and_binds() {
+    assert_eq!(
+        lines(
+            "P := struct |a: i32, b: i32|;
+            ter.out(match P(1, 2) of P(a, b) { a + b } else { 0 });"
+        ),
+        ["3"]
+    );
+    // without a catch-all, a match can give nothing
+    assert_eq!(
+        run("x := match 2 of 1 { 1 }; ter.out(x + 1);"),
+        Err("4.14.0")
+    );
+}
+
+#[test]
+fn conditions_must_be_bools() {
+    assert_eq!(run("while 1 { ter.out(1); };"), Err("4.17"));
+    assert_eq!(run("do { ter.out(1); } while \"a\";"), Err("4.17"));
+    assert_eq!(run("for {i := 0; i; i += 1} {};"), Err("4.17"));
+    assert_eq!(run("if 1 { ter.out(1) };"), Err("4.17"));
+    // `loop` and a `for` header without a condition run until they are broken out of
+    assert_eq!(
+        lines("for {i := 0; ; i += 1} { if i == 2 { break; }; ter.out(i); }; loop { break; };"),
+        ["0", "1"]
+    );
+}
@@ -0,0 +1,103 @@
+mod common;
+use common::{lines, run};
+
+const FIND: &str = "find := |xs: array<i32>, x: i32|: i32? {
+    for i in xs { if i == x { ret i } };
+    ret null
+};";
+
+#[test]
+fn nullable_values_are_narrowed_once_checked() {
+    assert_eq!(
+        lines(&format!(
+            "{} found := find([1, 2, 3], 2);
+            if found != null {{ ter.out(found + 1) }};
+            ter.out(find([1, 2, 3], 4) ?: 0, found != null && found > 1);",
+            FIND
+        )),
+        ["3", "0 true"]
+    );
+    assert_eq!(
+        run(&format!("{} ter.out(find([1], 1) + 1);", FIND)),
+        Err("4.14.0")
+    );
+    // assigning to a narrowed variable can make it null again
+    assert_eq!(
+        run(&format!(
+            "{} x := find([1], 1); if x != null {{ x = find([1], 2); ter.out(x + 1); }};",
+            FIND
+        )),
+        Err("4.14.0")
+    );
+    assert_eq!(run("x: i32 := null;"), Err("4.3"));
+}
+
+#[test]
+fn null_safe_access_and_assertion() {
+    assert_eq!(
+        lines(&format!(
+            "{} P := struct |a: i32|;
+            get := |b: bool|: P? {{ if b {{ P(1) }} else {{ null }} }};
+            ter.out(get(true)?.a, get(false)?.a, find([1], 1)!!);",
+            FIND
+        )),
+        ["1 null 1"]
+    );
+    assert_eq!(
+        run(&format!("{} ter.out(find([1], 2)!!);", FIND)),
+        Err("4.14.1")
+    );
+}
+
+#[test]
+fn errors_are_returned_by_errorable_procs_and_handled() {
+    assert_eq!(
+        lines(
+            "to_num := |s: str|: i32!? {
+                if s == \"\" { raise(\"empty string\") };
+                s @ i32
+            };
+            ter.out(to_num(\"12\") !?: |e| {0});
+            ter.out(to_num(\"\") !?: |e| { ter.out(e.code, e.message); -1 });"
+        ),
+        ["12", "5.0 empty string", "-1"]
+    );
+    assert_eq!(run("raise(\"oops\");"), Err("5.0"));
+    assert_eq!(run("raise(1);"), Err("4.9"));
+    // an errorable value has to be handled before it is used
+    assert_eq!(
+        run("f := |x: i32|: i32!? { x }; ter.out(f(1) + 1);"),
+        Err("4.15.0")
+    );
+}
+
+#[test]
+fn references_and_pointers_alias_variables() {
+    assert_eq!(
+        lines(
+            "bump := |n: ptr<i32>| { \\n = \\n + 1 };
+            count := 1;
+            bump(&>count);
+            r := &count;
+            ter.out(count, \\r);
+            v := vec[1, 2];
+            p := &>v;
+            \\p[0] = 5;
+            ter.out(v);"
+        ),
+        ["2 2", "vec[5, 2]"]
+    );
+    // references only read what they refer to
+    assert_eq!(run("x := 1; r := &x; \\r = 2;"), Err("4.16"));
+    // variables referred to can't be redeclared or deleted
+    assert_eq!(run("x := 1; r := &x; del x;"), Err("3.7"));
+    assert_eq!(run("x := 1; r := &x; x := 2;"), Err("3.7"));
+}
+
+#[test]
+fn raise_gives_way_to_a_declared_raise() {
+    assert_eq!(
+        lines("raise := |message: str| { ter.out(\"raised\", message); }; raise(\"oops\"); ter.out(1);"),
+        ["raised oops", "1"]
+    );
+}
@@ -0,0 +1,70 @@
+mod common;
+use common::{lines, run};
+
+#[test]
+fn compound_assignment_works_on_any_lvalue() {
+    assert_eq!(
+        lines(
+            "a := 4; a += 2; a++; c := 10; a >< c;
+            v := vec[1, 2]; v[0] += 5; v[1]--;
+            P := struct |n: i32|; p := P(1); p.n *= 3;
+            ter.out(a, c, v, p.n);"
+        ),
+        ["10 7 vec[6, 1] 3"]
+    );
+    assert_eq!(run("a := 1; b := \"x\"; a >< b;"), Err("4.3"));
+}
+
+#[test]
+fn integer_arithmetic_is_checked_for_overflow() {
+    assert_eq!(lines("x: u8 := 254; x++; ter.out(x);"), ["255"]);
+    assert_eq!(run("x: u8 := 255; x++;"), Err("4.1.1"));
+    assert_eq!(run("x: u8 := 255; x += 1;"), Err("4.1.2"));
+    assert_eq!(run("y: i8 := -128; y -= 1;"), Err("4.1.2"));
+    assert_eq!(run("x: i32 := 2147483647; ter.out(x * 2);"), Err("4.1.2"));
+}
+
+#[test]
+fn powers_roots_and_logarithms() {
+    assert_eq!(
+        lines("ter.out(3 ^ 2, 2 rt 16, 3 rt -27, 10 log 1000, 2 ^ 0.5 > 1.41);"),
+        ["9 4 -3 3 true"]
+    );
+    // big integers too large for a float still have logarithms
+    assert_eq!(
+        lines("ter.out(10 log (10 @ ibig) ^ 400, 2 log (2 @ ubig) ^ 1000);"),
+        ["400 1000"]
+    );
+    // powers of big integers too large to hold raise an error instead of running out of memory
+    assert_eq!(lines("ter.out((1 @ ubig) ^ 4000000000);"), ["1"]);
+    assert_eq!(run("ter.out((2 @ ibig) ^ 4000000000);"), Err("4.1.2"));
+    assert_eq!(run("ter.out(2 ^ -1);"), Err("4.1.2"));
+    assert_eq!(run("ter.out(0.0 ^ -1.0);"), Err("4.1.2"));
+    assert_eq!(run("ter.out(2 rt -4);"), Err("4.1.2"));
+    assert_eq!(run("ter.out(0 rt 4);"), Err("4.1.2"));
+    assert_eq!(run("ter.out(1 log 4);"), Err("4.1.2"));
+    assert_eq!(run("ter.out(10 log 0);"), Err("4.1.2"));
+}
+
+#[test]
+fn divisions_round_in_the_direction_they_are_named_after() {
+    assert_eq!(
+        lines("ter.out(-7 /f 2, -7 /c 2, -7 /~ 2, 7 ÷f 2, 7 ÷c 2, 7 ÷~ 2, 7 / 2);"),
+        ["-4 -3 -4 3 4 4 3"]
+    );
+    assert_eq!(run("ter.out(1 /f 0);"), Err("4.1.2"));
+    assert_eq!(run("ter.out(1.0 /c 0);"), Err("4.1.2"));
+    assert_eq!(run("ter.out(1 ÷~ 0);"), Err("4.1.2"));
+}
+
+#[test]
+fn every_division_treats_zero_and_overflow_the_same() {
+    for opr in ["/", "÷", "/f", "/c", "/~", "÷f", "÷c", "÷~"] {
+        assert_eq!(run(&format!("ter.out(1 {} 0);", opr)), Err("4.1.2"));
+        assert_eq!(run(&format!("ter.out(1.0 {} 0);", opr)), Err("4.1.2"));
+        assert_eq!(
+            run(&format!("a := -2147483647 - 1; ter.out(a {} -1);", opr)),
+            Err("4.1.2")
+        );
+    }
+}
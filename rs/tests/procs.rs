@@ -0,0 +1,146 @@
+mod common;
+use common::{lines, run};
+
+#[test]
+fn closures_keep_the_variables_they_were_defined_with() {
+    assert_eq!(
+        lines(
+            "make_counter := proc {
+                count := 0;
+                ret proc: i32 {count = count + 1; count};
+            };
+            a := make_counter();
+            b := make_counter();
+            a(); a();
+            ter.out(a(), b());"
+        ),
+        ["3 1"]
+    );
+    // fns can read the variables around them but not change them
+    assert_eq!(run("x := 1; f := fn { x = 2; }; f();"), Err("3.3"));
+}
+
+#[test]
+fn procs_recurse_and_call_each_other() {
+    assert_eq!(
+        lines(
+            "fact := fn|n: i32|: i32 { if n <= 1 {1} else {n * fact(n - 1)} };
+            is_even := fn|n: i32|: bool {if n == 0 {true} else {is_odd(n - 1)}};
+            is_odd := fn|n: i32|: bool {if n == 0 {false} else {is_even(n - 1)}};
+            ter.out(fact(5), is_even(10));"
+        ),
+        ["120 true"]
+    );
+}
+
+#[test]
+fn recursion_stops_at_the_max_call_depth() {
+    let program = "f := |n: i32|: i32 { if n == 0 { 0 } else { 1 + f(n - 1) } }; ter.out(f(N));";
+    assert_eq!(lines(&program.replace('N', "900")), ["900"]);
+    assert_eq!(run(&program.replace('N', "2000")), Err("0.2"));
+}
+
+#[test]
+fn procs_only_refer_to_procs_declared_later_from_their_bodies() {
+    assert_eq!(run("ter.out(f(1)); f := |x: i32|: i32 {x};"), Err("3.0"));
+    // and are only called once those procs are declared
+    assert_eq!(
+        run("h := proc { f(1) }; h(); f := |x: i32|: i32 {x};"),
+        Err("3.8")
+    );
+    assert_eq!(
+        run("h := proc { f(1) }; g := proc { h() }; g(); f := |x: i32|: i32 {x};"),
+        Err("3.8")
+    );
+    assert_eq!(
+        lines("h := proc { f(1) }; f := |x: i32|: i32 {x}; g := proc { h() }; ter.out(g());"),
+        ["1"]
+    );
+}
+
+#[test]
+fn arguments_are_bound_by_position_and_keyword() {
+    assert_eq!(
+        lines(
+            "k := |a: i32, b: i32: 2, c: i32: 3| ter.out(a, b, c);
+            k(1, c: 4);
+            l := |first: i32, rest: #varg<i32>, opts: #vkwarg<str, bool>| ter.out(first, rest, opts);
+            l(1, 2, 3, verbose: true);"
+        ),
+        ["1 2 4", "1 [2, 3] dict[\"verbose\": true]"]
+    );
+}
+
+#[test]
+fn arguments_are_checked() {
+    assert_eq!(run("f := |a: i32| a; f();"), Err("2.3"));
+    assert_eq!(run("f := |a: i32| a; f(1, 2);"), Err("2.5.0"));
+    assert_eq!(run("f := |a: i32| a; f(1, b: 2);"), Err("2.5.1"));
+    assert_eq!(run("f := |a: i32| a; f(1, a: 2);"), Err("2.5.2"));
+    assert_eq!(run("f := |a: i32| a; f(\"x\");"), Err("4.9"));
+    // numbers are converted to the type of the parameter
+    assert_eq!(
+        lines("half := fn|x: f64|: f64 {x / 2.0}; ter.out(half(3));"),
+        ["1.5"]
+    );
+}
+
+#[test]
+fn proc_types_are_checked() {
+    assert_eq!(
+        lines("f: proc<[i32], i32> := |x: i32|: i32 {x + 1}; ter.out(f(1));"),
+        ["2"]
+    );
+    assert_eq!(
+        run("f: proc<[str], i32> := |x: i32|: i32 {x + 1};"),
+        Err("4.3")
+    );
+}
+
+#[test]
+fn generics_are_inferred_from_the_arguments() {
+    assert_eq!(
+        lines(
+            "m := fn<T>|a: T, b: T|: T {a};
+            Pair := struct<T> |first: T, second: T|;
+            ter.out(m(1, 2), m(\"a\", \"b\"), Pair(1, 2).first + 1);"
+        ),
+        ["1 a 2"]
+    );
+}
+
+#[test]
+fn overloads_are_chosen_by_the_types_of_the_arguments() {
+    let describe = "describe := |n: i32|: str {\"a number\"} |s: str|: str {\"a string\"};";
+    assert_eq!(
+        lines(&format!(
+            "{} ter.out(describe(1), describe(\"a\"));",
+            describe
+        )),
+        ["a number a string"]
+    );
+    assert_eq!(run(&format!("{} describe(true);", describe)), Err("4.13.0"));
+    // numbers are converted the same way whether the overload is chosen when checking or when running
+    assert_eq!(
+        lines(&format!(
+            "{} g := proc|y| {{describe(y)}}; ter.out(describe(1.5), g(1.5));",
+            describe
+        )),
+        ["a number a number"]
+    );
+    assert_eq!(
+        run("h := |x: i32|: i32 {x} |x: i64|: i64 {x}; h(1.5);"),
+        Err("4.13.1")
+    );
+}
+
+#[test]
+fn infix_procs_take_the_precedence_they_are_given() {
+    assert_eq!(
+        lines(
+            "plus_half := infix<8> |a: i32, b: i32|: i32 {a + b ÷ 2};
+            ter.out(1 plus_half 4 + 1);"
+        ),
+        ["4"]
+    );
+}
@@ -0,0 +1,44 @@
+mod common;
+use common::{lines, run};
+
+#[test]
+fn arrays_and_vecs_are_indexed_and_sliced() {
+    assert_eq!(
+        lines("arr := [1, 2, 3]; ter.out(arr[0], arr[-1], arr[1..], len(arr));"),
+        ["1 3 [2, 3] 3"]
+    );
+    assert_eq!(
+        lines("v := vec[\"a\", \"b\"]; v[0] = \"z\"; ter.out(v ~ vec[\"c\"]);"),
+        ["vec[\"z\", \"b\", \"c\"]"]
+    );
+}
+
+#[test]
+fn indexing_checks_bounds_and_mutability() {
+    assert_eq!(run("arr := [1, 2]; ter.out(arr[2]);"), Err("4.8.2"));
+    assert_eq!(run("arr := [1, 2]; arr[0] = 3;"), Err("4.8.3"));
+    assert_eq!(run("d := dict[\"a\": 1]; ter.out(d[\"b\"]);"), Err("4.8.5"));
+    assert_eq!(run("ter.out(len(1));"), Err("4.8.0"));
+}
+
+#[test]
+fn collections_hold_their_items() {
+    assert_eq!(
+        lines(
+            "pair := tuple[1, \"one\"];
+            ids := set[3, 1, 3];
+            ages := dict[\"alice\": 30, \"bob\": 25];
+            ages[\"carol\"] = 41;
+            ter.out(pair[1], len(ids), ages[\"carol\"], len(ages));"
+        ),
+        ["one 2 41 3"]
+    );
+}
+
+#[test]
+fn len_gives_way_to_a_declared_len() {
+    assert_eq!(
+        lines("ter.out(len([1, 2])); len := |x: i32|: i32 {x + 100}; ter.out(len(1));"),
+        ["2", "101"]
+    );
+}